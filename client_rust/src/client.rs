@@ -1,15 +1,32 @@
-use chikin_airdrop_pool::state::{AirdropPool, AirdropClaimer};
+use std::str::FromStr;
+
+use chikin_airdrop_pool::state::{AirdropPool, AirdropClaimer, AirdropDepositor};
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use chikin_airdrop_pool::packable::Packable;
+use chikin_airdrop_pool::config as program_config;
+use spl_token_2022::extension::{ExtensionType, StateWithExtensions};
+use spl_token_2022::state::Account as TokenAccount;
+use spl_token_2022::state::Mint as TokenMint;
 
 type Error = Box<dyn std::error::Error>;
 
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReferralStats {
+    pub direct_referrals: u64,
+    pub indirect_referrals: u64,
+    pub referral_rewards_earned: u64,
+    pub pending_referral_rewards: u64,
+}
+
 pub fn get_airdrop_pool(
     rpc_client: &RpcClient,
     address: &Pubkey,
 ) -> Result<AirdropPool, Error> {
     let data = rpc_client.get_account_data(address)?;
+    // Pools that were not migrated yet are unpacked with their previous layout
     let object = AirdropPool::unpack(&data)
         .map_err(|e| format!("Invalid airdrop pool {}: {:?}", address, e))?;
     Ok(object)
@@ -25,3 +42,80 @@ pub fn get_airdrop_user(
     Ok(object)
 }
 
+pub fn get_airdrop_depositor(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+) -> Result<AirdropDepositor, Error> {
+    let data = rpc_client.get_account_data(address)?;
+    let object = AirdropDepositor::unpack(&data)
+        .map_err(|e| format!("Invalid airdrop depositor {}: {:?}", address, e))?;
+    Ok(object)
+}
+
+// The token program of a mint is its owner, SPL Token or Token-2022. Pools of the native mint are
+// native SOL pools, which use the system program
+pub fn get_token_program(
+    rpc_client: &RpcClient,
+    token_mint: &Pubkey,
+) -> Result<Pubkey, Error> {
+    if token_mint == &spl_token::native_mint::id() {
+        return Ok(solana_program::system_program::id());
+    }
+    let account = rpc_client.get_account(token_mint)?;
+    Ok(account.owner)
+}
+
+// Size of a new token account of [token_mint], Token-2022 mints may require account extensions
+pub fn get_token_account_len(
+    rpc_client: &RpcClient,
+    token_mint: &Pubkey,
+) -> Result<usize, Error> {
+    let account = rpc_client.get_account(token_mint)?;
+    if account.owner == spl_token::id() {
+        return Ok(spl_token::state::Account::LEN);
+    }
+    let token_mint_state = StateWithExtensions::<TokenMint>::unpack(&account.data)
+        .map_err(|e| format!("Invalid token mint {}: {:?}", token_mint, e))?;
+    let mint_extensions = token_mint_state.get_extension_types()
+        .map_err(|e| format!("Invalid token mint {}: {:?}", token_mint, e))?;
+    let account_extensions = ExtensionType::get_required_init_account_extensions(&mint_extensions);
+    Ok(ExtensionType::get_account_len::<TokenAccount>(&account_extensions))
+}
+
+pub fn get_referral_stats(
+    rpc_client: &RpcClient,
+    program: &Pubkey,
+    wallet: &Pubkey,
+    pool_account: &Pubkey,
+) -> Result<ReferralStats, Error> {
+    let claimer_account = program_config::get_claimer_account(program, pool_account, wallet).0;
+    let claimer_account_state = get_airdrop_user(rpc_client, &claimer_account)?;
+    Ok(ReferralStats {
+        direct_referrals: claimer_account_state.direct_referrals,
+        indirect_referrals: claimer_account_state.indirect_referrals,
+        referral_rewards_earned: claimer_account_state.referral_rewards_earned,
+        pending_referral_rewards: claimer_account_state.pending_referral_rewards,
+    })
+}
+
+// Token account of [owner] holding the most tokens of [token_mint], with its amount
+pub fn get_largest_token_account(
+    rpc_client: &RpcClient,
+    owner: &Pubkey,
+    token_mint: &Pubkey,
+) -> Result<Option<(Pubkey, u64)>, Error> {
+    let keyed_accounts = rpc_client.get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(*token_mint))?;
+    let mut result: Option<(Pubkey, u64)> = None;
+    for keyed_account in keyed_accounts {
+        let address = Pubkey::from_str(&keyed_account.pubkey)?;
+        let data = rpc_client.get_account_data(&address)?;
+        let amount = StateWithExtensions::<TokenAccount>::unpack(&data)
+            .map_err(|e| format!("Invalid token account {}: {:?}", address, e))?
+            .base
+            .amount;
+        if result.map_or(true, |(_, largest_amount)| amount > largest_amount) {
+            result = Some((address, amount));
+        }
+    }
+    Ok(result)
+}
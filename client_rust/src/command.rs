@@ -1,4 +1,20 @@
-use chikin_airdrop_pool::instruction::AirdropPoolInstruction;
+use std::str::FromStr;
+
+use chikin_airdrop_pool::instruction::{
+    AddRewardMintAccountKeys,
+    AirdropPoolInstruction,
+    ClaimAccountKeys,
+    ClosePoolAccountKeys,
+    DepositAccountKeys,
+    DistributeAccountKeys,
+    InitializeArgs,
+    WithdrawAccountKeys,
+};
+use chikin_airdrop_pool::packable::Packable;
+use chikin_airdrop_pool::state::{AirdropClaimer, AirdropDepositor, AirdropPool};
+use chikin_airdrop_pool::token;
+use chikin_airdrop_pool::voucher::{self, Voucher};
+use solana_sdk::instruction::Instruction;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{Keypair, Signer};
 use solana_sdk::transaction::Transaction;
@@ -14,50 +30,37 @@ type CommandResult = Result<(), Error>;
 pub fn initialize(
     config: &Config,
     token_mint: Pubkey,
-    pool_account_nonce: [u8; 4],
-    reward_per_account: u64,
-    reward_per_referral: u64,
-    max_referral_depth: u8,
+    args: InitializeArgs,
 ) -> CommandResult {
-    let mut transaction = Transaction::new_with_payer(
-        &[
-            AirdropPoolInstruction::initialize(
-                config.fee_payer.pubkey(),
-                config.id_config.program,
-                config.id_config.rent_sysvar,
-                config.id_config.system_program,
-                config.id_config.token_program,
-                token_mint,
-                pool_account_nonce,
-                reward_per_account,
-                reward_per_referral,
-                max_referral_depth,
-            ),
-        ],
-        Some(&config.fee_payer.pubkey()),
-    );
-
-    let (recent_blockhash, _fee_calculator) = config.rpc_client.get_recent_blockhash()?;
+    let token_program = client::get_token_program(&config.rpc_client, &token_mint)?;
 
-    config.check_fee_payer_balance(1)?; // TODO
-
-    let mut signers = vec![
-        config.fee_payer.as_ref()
-    ];
-    signers.sort_by_key(|e| e.pubkey());
-    signers.dedup();
-
-    transaction.sign(&signers, recent_blockhash);
+    let instruction = AirdropPoolInstruction::initialize(
+        config.fee_payer.pubkey(),
+        config.id_config.program,
+        config.id_config.rent_sysvar,
+        config.id_config.system_program,
+        token_program,
+        token_mint,
+        args,
+    );
 
-    config.send_transaction(transaction)?;
+    // Rent of the pool account and of the pool token account, native SOL pools hold their lamports in the pool account
+    let mut required_balance = config.rpc_client.get_minimum_balance_for_rent_exemption(AirdropPool::PACKED_SIZE)?;
+    if !token::is_native(&token_program) {
+        let token_account_len = client::get_token_account_len(&config.rpc_client, &token_mint)?;
+        required_balance += config.rpc_client.get_minimum_balance_for_rent_exemption(token_account_len)?;
+    }
 
-    Ok(())
+    send_instructions(config, &[instruction], &[], required_balance)
 }
 
-pub fn claim(config: &Config, token_mint: Pubkey, pool_account: Pubkey, claimer_wallet: &Keypair, referrer_wallet: Option<Pubkey>) -> CommandResult {
+pub fn claim(config: &Config, token_mint: Pubkey, pool_account: Pubkey, claimer_wallet: &Keypair, referrer_wallet: Option<Pubkey>, voucher: Option<Voucher>) -> CommandResult {
     let pool_account_state = client::get_airdrop_pool(&config.rpc_client, &pool_account)?;
 
-    // Pack referrers
+    // Pack referrers, the program rejects self-referrals and chains visiting a wallet twice
+    if referrer_wallet == Some(claimer_wallet.pubkey()) {
+        return Err(AirdropPoolClientError::SelfReferral.into());
+    }
     let mut referrer_wallet_list = vec![];
     let mut tmp_referrer_wallet_option = referrer_wallet;
     let mut tmp_referrer_depth = 1;
@@ -65,6 +68,9 @@ pub fn claim(config: &Config, token_mint: Pubkey, pool_account: Pubkey, claimer_
         if tmp_referrer_depth > pool_account_state.max_referral_depth {
             break;
         }
+        if tmp_referrer_wallet == claimer_wallet.pubkey() || referrer_wallet_list.contains(&tmp_referrer_wallet) {
+            return Err(AirdropPoolClientError::ReferralCycle.into());
+        }
         let referrer_account = program_config::get_claimer_account(&config.id_config.program, &pool_account, &tmp_referrer_wallet).0;
         let referrer_account_state = client::get_airdrop_user(&config.rpc_client, &referrer_account)?;
         if referrer_account_state.claimed == 0 {
@@ -76,33 +82,369 @@ pub fn claim(config: &Config, token_mint: Pubkey, pool_account: Pubkey, claimer_
         tmp_referrer_depth += 1;
     }
 
+    let keys = get_claim_account_keys(config, &pool_account_state, token_mint, pool_account, claimer_wallet.pubkey())?;
+
     // Build transaction
+    let mut instructions = vec![];
+    if let Some(voucher_signer) = pool_account_state.voucher_signer {
+        let voucher = voucher.ok_or(AirdropPoolClientError::VoucherRequired)?;
+        let message = voucher::get_message(&pool_account, &claimer_wallet.pubkey(), voucher.expiry_slot);
+        instructions.push(voucher::new_ed25519_instruction(&voucher_signer, &voucher.signature, &message));
+    }
+    instructions.push(AirdropPoolInstruction::claim(&keys, &referrer_wallet_list));
+
+    let required_balance = get_claim_required_balance(config, &pool_account_state, token_mint, &claimer_wallet.pubkey())?;
+
+    send_instructions(config, &instructions, &[claimer_wallet], required_balance)
+}
+
+pub fn claim_with_proof(
+    config: &Config,
+    token_mint: Pubkey,
+    pool_account: Pubkey,
+    claimer_wallet: &Keypair,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> CommandResult {
+    let pool_account_state = client::get_airdrop_pool(&config.rpc_client, &pool_account)?;
+    let keys = get_claim_account_keys(config, &pool_account_state, token_mint, pool_account, claimer_wallet.pubkey())?;
+    let instruction = AirdropPoolInstruction::claim_with_proof(&keys, amount, proof);
+
+    let required_balance = get_claim_required_balance(config, &pool_account_state, token_mint, &claimer_wallet.pubkey())?;
+
+    send_instructions(config, &[instruction], &[claimer_wallet], required_balance)
+}
+
+pub fn release(
+    config: &Config,
+    token_mint: Pubkey,
+    pool_account: Pubkey,
+    claimer_wallet: Pubkey,
+) -> CommandResult {
+    let pool_account_state = client::get_airdrop_pool(&config.rpc_client, &pool_account)?;
+
+    let instruction = AirdropPoolInstruction::release(
+        config.id_config.program,
+        config.id_config.clock_sysvar,
+        pool_account_state.token_program_id,
+        token_mint,
+        pool_account,
+        claimer_wallet,
+    );
+
+    send_instructions(config, &[instruction], &[], 0)
+}
+
+pub fn withdraw_referral_rewards(
+    config: &Config,
+    token_mint: Pubkey,
+    pool_account: Pubkey,
+    referrer_wallet: Pubkey,
+) -> CommandResult {
+    let pool_account_state = client::get_airdrop_pool(&config.rpc_client, &pool_account)?;
+
+    let instruction = AirdropPoolInstruction::withdraw_referral_rewards(
+        config.id_config.program,
+        pool_account_state.token_program_id,
+        token_mint,
+        pool_account,
+        referrer_wallet,
+        &get_reward_mint_list(&pool_account_state),
+    );
+
+    send_instructions(config, &[instruction], &[], 0)
+}
+
+pub fn deposit(
+    config: &Config,
+    token_mint: Pubkey,
+    pool_account: Pubkey,
+    amount: u64,
+) -> CommandResult {
+    let pool_account_state = client::get_airdrop_pool(&config.rpc_client, &pool_account)?;
+    let depositor_token_account = program_config::get_claimer_token_account(&pool_account_state.token_program_id, &token_mint, &config.fee_payer.pubkey());
+
+    let keys = DepositAccountKeys {
+        depositor_wallet: config.fee_payer.pubkey(),
+        program: config.id_config.program,
+        rent_sysvar: config.id_config.rent_sysvar,
+        system_program: config.id_config.system_program,
+        token_program: pool_account_state.token_program_id,
+        token_mint,
+        pool_account,
+        depositor_token_account,
+    };
+    let instruction = AirdropPoolInstruction::deposit(&keys, amount);
+
+    // Rent of the depositor account, created by the first deposit
+    let depositor_account = program_config::get_depositor_account(&config.id_config.program, &pool_account, &config.fee_payer.pubkey()).0;
+    let mut required_balance = 0;
+    if config.rpc_client.get_balance(&depositor_account)? == 0 {
+        required_balance += config.rpc_client.get_minimum_balance_for_rent_exemption(AirdropDepositor::PACKED_SIZE)?;
+    }
+
+    send_instructions(config, &[instruction], &[], required_balance)
+}
+
+// Claimers are migrated along with the pool, pass the claimers which were not migrated yet. Pools created
+// before the authority need the upgrade authority of the program, which becomes their authority
+pub fn migrate_pool(
+    config: &Config,
+    pool_account: Pubkey,
+    upgrade_authority: Option<&Keypair>,
+    claimer_wallet_list: &[Pubkey],
+) -> CommandResult {
+    let instruction = AirdropPoolInstruction::migrate_pool(
+        config.fee_payer.pubkey(),
+        config.id_config.program,
+        config.id_config.system_program,
+        pool_account,
+        upgrade_authority.map(|upgrade_authority| upgrade_authority.pubkey()),
+        claimer_wallet_list,
+    );
+
+    // Rent of the additional bytes of the pool and of the claimers, the pool may have been migrated already
+    let mut required_balance = get_migration_rent(config, &pool_account, AirdropPool::PACKED_SIZE)?;
+    for claimer_wallet in claimer_wallet_list {
+        let claimer_account = program_config::get_claimer_account(&config.id_config.program, &pool_account, claimer_wallet).0;
+        required_balance += get_migration_rent(config, &claimer_account, AirdropClaimer::PACKED_SIZE)?;
+    }
+
+    let signers: Vec<&dyn Signer> = upgrade_authority.into_iter()
+        .map(|upgrade_authority| upgrade_authority as &dyn Signer)
+        .collect();
+    send_instructions(config, &[instruction], &signers, required_balance)
+}
+
+pub fn close_claimer(
+    config: &Config,
+    pool_account: Pubkey,
+    claimer_wallet: &Keypair,
+) -> CommandResult {
+    let instruction = AirdropPoolInstruction::close_claimer(
+        config.id_config.program,
+        config.id_config.clock_sysvar,
+        pool_account,
+        claimer_wallet.pubkey(),
+    );
+
+    send_instructions(config, &[instruction], &[claimer_wallet], 0)
+}
+
+pub fn update_pool(
+    config: &Config,
+    pool_account: Pubkey,
+    reward_per_account: u64,
+    reward_per_referral: u64,
+    max_referral_depth: u8,
+) -> CommandResult {
+    let instruction = AirdropPoolInstruction::update_pool(
+        config.fee_payer.pubkey(),
+        config.id_config.program,
+        pool_account,
+        reward_per_account,
+        reward_per_referral,
+        max_referral_depth,
+    );
+
+    send_instructions(config, &[instruction], &[], 0)
+}
+
+pub fn add_reward_mint(
+    config: &Config,
+    pool_account: Pubkey,
+    reward_mint: Pubkey,
+    reward_per_account: u64,
+    reward_per_referral: u64,
+) -> CommandResult {
+    let pool_account_state = client::get_airdrop_pool(&config.rpc_client, &pool_account)?;
+
+    let keys = AddRewardMintAccountKeys {
+        authority: config.fee_payer.pubkey(),
+        program: config.id_config.program,
+        rent_sysvar: config.id_config.rent_sysvar,
+        system_program: config.id_config.system_program,
+        token_program: pool_account_state.token_program_id,
+        reward_mint,
+        pool_account,
+    };
+    let instruction = AirdropPoolInstruction::add_reward_mint(&keys, reward_per_account, reward_per_referral);
+
+    // Rent of the pool reward token account
+    let reward_token_account_len = client::get_token_account_len(&config.rpc_client, &reward_mint)?;
+    let required_balance = config.rpc_client.get_minimum_balance_for_rent_exemption(reward_token_account_len)?;
+
+    send_instructions(config, &[instruction], &[], required_balance)
+}
+
+// The available tokens of the pool reward token accounts of [reward_mint_list] go to the associated token accounts
+// of the authority, which are created when missing
+pub fn withdraw(
+    config: &Config,
+    token_mint: Pubkey,
+    pool_account: Pubkey,
+    destination_token_account: Pubkey,
+    amount: u64,
+    reward_mint_list: &[Pubkey],
+) -> CommandResult {
+    let pool_account_state = client::get_airdrop_pool(&config.rpc_client, &pool_account)?;
+
+    let (mut instructions, required_balance) = get_create_token_account_instructions(config, &pool_account_state, reward_mint_list, &config.fee_payer.pubkey())?;
+    instructions.push(AirdropPoolInstruction::withdraw(&WithdrawAccountKeys {
+        authority: config.fee_payer.pubkey(),
+        program: config.id_config.program,
+        token_program: pool_account_state.token_program_id,
+        token_mint,
+        pool_account,
+        destination_token_account,
+        reward_mint_list: reward_mint_list.to_vec(),
+    }, amount));
+
+    send_instructions(config, &instructions, &[], required_balance)
+}
+
+// Recipients per Distribute transaction, bounded by the transaction size and the compute budget
+pub const DISTRIBUTE_CHUNK_SIZE: usize = 5;
+
+// Pushes tokens to the recipients of a CSV file with one "wallet,amount" line per recipient.
+// Recipients which already claimed are skipped, so that an interrupted distribution can be resumed,
+// and their wallets are returned for the caller to report.
+pub fn distribute(
+    config: &Config,
+    token_mint: Pubkey,
+    pool_account: Pubkey,
+    recipient_csv_path: &str,
+) -> Result<Vec<Pubkey>, Error> {
+    let pool_account_state = client::get_airdrop_pool(&config.rpc_client, &pool_account)?;
+    let recipient_list = read_recipient_csv(recipient_csv_path)?;
+
+    let mut pending_recipient_list = vec![];
+    let mut skipped_wallet_list = vec![];
+    for (recipient_wallet, amount) in recipient_list {
+        let recipient_account = program_config::get_claimer_account(&config.id_config.program, &pool_account, &recipient_wallet).0;
+        if config.rpc_client.get_balance(&recipient_account)? > 0 {
+            skipped_wallet_list.push(recipient_wallet);
+            continue;
+        }
+        pending_recipient_list.push((recipient_wallet, amount));
+    }
+
+    let claimer_account_rent = config.rpc_client.get_minimum_balance_for_rent_exemption(AirdropClaimer::PACKED_SIZE)?;
+    // Native SOL pools pay the recipient wallets themselves
+    let is_native = token::is_native(&pool_account_state.token_program_id);
+    let token_account_rent = if is_native {
+        0
+    } else {
+        let token_account_len = client::get_token_account_len(&config.rpc_client, &token_mint)?;
+        config.rpc_client.get_minimum_balance_for_rent_exemption(token_account_len)?
+    };
+
+    let keys = DistributeAccountKeys {
+        authority: config.fee_payer.pubkey(),
+        program: config.id_config.program,
+        rent_sysvar: config.id_config.rent_sysvar,
+        clock_sysvar: config.id_config.clock_sysvar,
+        system_program: config.id_config.system_program,
+        token_program: pool_account_state.token_program_id,
+        associated_token_program: config.id_config.associated_token_program,
+        token_mint,
+        pool_account,
+    };
+    for recipient_chunk in pending_recipient_list.chunks(DISTRIBUTE_CHUNK_SIZE) {
+        let instruction = AirdropPoolInstruction::distribute(&keys, recipient_chunk);
+
+        // The authority pays the rent of the claimer accounts and of the missing token accounts
+        let mut required_balance = 0;
+        for (recipient_wallet, _) in recipient_chunk {
+            required_balance += claimer_account_rent;
+            let recipient_token_account = program_config::get_claimer_token_account(&pool_account_state.token_program_id, &token_mint, recipient_wallet);
+            if !is_native && config.rpc_client.get_balance(&recipient_token_account)? == 0 {
+                required_balance += token_account_rent;
+            }
+        }
+
+        send_instructions(config, &[instruction], &[], required_balance)?;
+    }
+
+    Ok(skipped_wallet_list)
+}
+
+pub fn close_pool(
+    config: &Config,
+    token_mint: Pubkey,
+    pool_account: Pubkey,
+    destination_wallet: Pubkey,
+) -> CommandResult {
+    let pool_account_state = client::get_airdrop_pool(&config.rpc_client, &pool_account)?;
+    let destination_token_account = program_config::get_claimer_token_account(&pool_account_state.token_program_id, &token_mint, &destination_wallet);
+    let reward_mint_list = get_reward_mint_list(&pool_account_state);
+
+    // The remaining reward mint tokens go to the associated token accounts of the destination wallet
+    let (mut instructions, required_balance) = get_create_token_account_instructions(config, &pool_account_state, &reward_mint_list, &destination_wallet)?;
+    instructions.push(AirdropPoolInstruction::close_pool(&ClosePoolAccountKeys {
+        authority: config.fee_payer.pubkey(),
+        program: config.id_config.program,
+        token_program: pool_account_state.token_program_id,
+        token_mint,
+        pool_account,
+        destination_token_account,
+        destination_wallet,
+        reward_mint_list,
+    }));
+
+    send_instructions(config, &instructions, &[], required_balance)
+}
+
+pub fn pause(
+    config: &Config,
+    pool_account: Pubkey,
+) -> CommandResult {
+    let instruction = AirdropPoolInstruction::pause(
+        config.fee_payer.pubkey(),
+        config.id_config.program,
+        pool_account,
+    );
+
+    send_instructions(config, &[instruction], &[], 0)
+}
+
+pub fn resume(
+    config: &Config,
+    pool_account: Pubkey,
+) -> CommandResult {
+    let instruction = AirdropPoolInstruction::resume(
+        config.fee_payer.pubkey(),
+        config.id_config.program,
+        pool_account,
+    );
+
+    send_instructions(config, &[instruction], &[], 0)
+}
+
+// Signs with the fee payer and [signer_list], then sends once the fee payer holds the transaction fee plus
+// [required_balance], usually the rent of the accounts the transaction creates
+fn send_instructions(
+    config: &Config,
+    instructions: &[Instruction],
+    signer_list: &[&dyn Signer],
+    required_balance: u64,
+) -> CommandResult {
     let mut transaction = Transaction::new_with_payer(
-        &[
-            AirdropPoolInstruction::claim(
-                config.id_config.program,
-                config.id_config.rent_sysvar,
-                config.id_config.system_program,
-                config.id_config.token_program,
-                token_mint,
-                pool_account,
-                claimer_wallet.pubkey(),
-                &referrer_wallet_list,
-            ),
-        ],
+        instructions,
         Some(&config.fee_payer.pubkey()),
     );
 
-    let (recent_blockhash, _fee_calculator) = config.rpc_client.get_recent_blockhash()?;
+    let (recent_blockhash, fee_calculator) = config.rpc_client.get_recent_blockhash()?;
 
-    config.check_fee_payer_balance(1)?; // TODO
+    let transaction_fee = fee_calculator.lamports_per_signature * transaction.message.header.num_required_signatures as u64;
+    config.check_fee_payer_balance(transaction_fee + required_balance)?;
 
     let mut signers = vec![
-        config.fee_payer.as_ref(),
-        claimer_wallet
+        config.fee_payer.as_ref()
     ];
+    signers.extend_from_slice(signer_list);
     signers.sort_by_key(|e| e.pubkey());
-    signers.dedup();
+    signers.dedup_by_key(|e| e.pubkey());
 
     transaction.sign(&signers, recent_blockhash);
 
@@ -111,3 +453,145 @@ pub fn claim(config: &Config, token_mint: Pubkey, pool_account: Pubkey, claimer_
     Ok(())
 }
 
+// The claimer wallet pays the claim fee, the fee payer the rent of the claimer accounts
+fn get_claim_required_balance(
+    config: &Config,
+    pool_account_state: &AirdropPool,
+    token_mint: Pubkey,
+    claimer_wallet: &Pubkey,
+) -> Result<u64, Error> {
+    let claim_rent = get_claim_rent(config, pool_account_state, token_mint, claimer_wallet)?;
+    let claim_fee = pool_account_state.claim_fee_lamports;
+    if claimer_wallet == &config.fee_payer.pubkey() {
+        return Ok(claim_rent + claim_fee);
+    }
+    config.check_balance(claimer_wallet, claim_fee)?;
+    Ok(claim_rent)
+}
+
+// Rent the payer adds to [account] to grow it to [packed_size] bytes
+fn get_migration_rent(
+    config: &Config,
+    account: &Pubkey,
+    packed_size: usize,
+) -> Result<u64, Error> {
+    let required_lamports = config.rpc_client.get_minimum_balance_for_rent_exemption(packed_size)?;
+    Ok(required_lamports.saturating_sub(config.rpc_client.get_balance(account)?))
+}
+
+// Accounts of a claim, with the optional accounts the pool requires
+fn get_claim_account_keys(
+    config: &Config,
+    pool_account_state: &AirdropPool,
+    token_mint: Pubkey,
+    pool_account: Pubkey,
+    claimer_wallet: Pubkey,
+) -> Result<ClaimAccountKeys, Error> {
+    Ok(ClaimAccountKeys {
+        payer: config.fee_payer.pubkey(),
+        program: config.id_config.program,
+        rent_sysvar: config.id_config.rent_sysvar,
+        clock_sysvar: config.id_config.clock_sysvar,
+        instructions_sysvar: config.id_config.instructions_sysvar,
+        system_program: config.id_config.system_program,
+        token_program: pool_account_state.token_program_id,
+        associated_token_program: config.id_config.associated_token_program,
+        token_mint,
+        pool_account,
+        claimer_wallet,
+        gate_token_account: get_gate_token_account(config, pool_account_state, &claimer_wallet)?,
+        fee_recipient: pool_account_state.get_fee_recipient(),
+        reward_mint_list: get_reward_mint_list(pool_account_state),
+    })
+}
+
+// Token account of the claimer holding the most tokens of the gate mint, for token gated pools
+fn get_gate_token_account(
+    config: &Config,
+    pool_account_state: &AirdropPool,
+    claimer_wallet: &Pubkey,
+) -> Result<Option<Pubkey>, Error> {
+    let gate_mint = match pool_account_state.gate_mint {
+        Some(gate_mint) => gate_mint,
+        None => return Ok(None),
+    };
+    let (gate_token_account, amount) = client::get_largest_token_account(&config.rpc_client, claimer_wallet, &gate_mint)?
+        .ok_or(AirdropPoolClientError::GateRequirementNotMet)?;
+    if amount < pool_account_state.gate_min_amount {
+        return Err(AirdropPoolClientError::GateRequirementNotMet.into());
+    }
+    Ok(Some(gate_token_account))
+}
+
+// Rent of the accounts a claim creates, paid by the fee payer
+fn get_claim_rent(
+    config: &Config,
+    pool_account_state: &AirdropPool,
+    token_mint: Pubkey,
+    claimer_wallet: &Pubkey,
+) -> Result<u64, Error> {
+    let mut required_balance = config.rpc_client.get_minimum_balance_for_rent_exemption(AirdropClaimer::PACKED_SIZE)?;
+    // Native SOL pools pay the claimer wallet itself, no token account is created
+    if token::is_native(&pool_account_state.token_program_id) {
+        return Ok(required_balance);
+    }
+    // Token-2022 accounts with extensions need a bit more
+    let mut token_mint_list = vec![token_mint];
+    token_mint_list.extend(get_reward_mint_list(pool_account_state));
+    for token_mint in token_mint_list {
+        let token_account = program_config::get_claimer_token_account(&pool_account_state.token_program_id, &token_mint, claimer_wallet);
+        if config.rpc_client.get_balance(&token_account)? == 0 {
+            let token_account_len = client::get_token_account_len(&config.rpc_client, &token_mint)?;
+            required_balance += config.rpc_client.get_minimum_balance_for_rent_exemption(token_account_len)?;
+        }
+    }
+    Ok(required_balance)
+}
+
+// Creates the missing associated token accounts of [wallet], the fee payer pays their rent
+fn get_create_token_account_instructions(
+    config: &Config,
+    pool_account_state: &AirdropPool,
+    token_mint_list: &[Pubkey],
+    wallet: &Pubkey,
+) -> Result<(Vec<Instruction>, u64), Error> {
+    let mut instructions = vec![];
+    let mut required_balance = 0;
+    for token_mint in token_mint_list {
+        let token_account = program_config::get_claimer_token_account(&pool_account_state.token_program_id, token_mint, wallet);
+        if config.rpc_client.get_balance(&token_account)? == 0 {
+            instructions.push(spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &config.fee_payer.pubkey(),
+                wallet,
+                token_mint,
+                &pool_account_state.token_program_id,
+            ));
+            let token_account_len = client::get_token_account_len(&config.rpc_client, token_mint)?;
+            required_balance += config.rpc_client.get_minimum_balance_for_rent_exemption(token_account_len)?;
+        }
+    }
+    Ok((instructions, required_balance))
+}
+
+fn get_reward_mint_list(pool_account_state: &AirdropPool) -> Vec<Pubkey> {
+    pool_account_state.get_reward_mints().iter()
+        .map(|(_, reward_mint)| reward_mint.token_mint_id)
+        .collect()
+}
+
+// Reads "wallet,amount" lines, blank lines are ignored
+fn read_recipient_csv(path: &str) -> Result<Vec<(Pubkey, u64)>, Error> {
+    let mut recipient_list = vec![];
+    for (index, line) in std::fs::read_to_string(path)?.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid_recipient = AirdropPoolClientError::InvalidRecipient { line: index + 1 };
+        let (wallet, amount) = line.split_once(',').ok_or(invalid_recipient)?;
+        let wallet = Pubkey::from_str(wallet.trim()).map_err(|_| invalid_recipient)?;
+        let amount = amount.trim().parse::<u64>().map_err(|_| invalid_recipient)?;
+        recipient_list.push((wallet, amount));
+    }
+    Ok(recipient_list)
+}
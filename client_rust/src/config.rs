@@ -47,7 +47,13 @@ impl Config {
 
     pub fn check_fee_payer_balance(&self,
                                    required_balance: u64) -> AirdropPoolClientResult<()> {
-        let balance = self.rpc_client.get_balance(&self.fee_payer.pubkey())
+        self.check_balance(&self.fee_payer.pubkey(), required_balance)
+    }
+
+    pub fn check_balance(&self,
+                         wallet: &Pubkey,
+                         required_balance: u64) -> AirdropPoolClientResult<()> {
+        let balance = self.rpc_client.get_balance(wallet)
             .map_err(|_| AirdropPoolClientError::RpcClientError)?;
         if balance < required_balance {
             Err(AirdropPoolClientError::InsufficientBalanceForFees {
@@ -68,8 +74,11 @@ impl Config {
 pub struct IdConfig {
     pub program: Pubkey,
     pub rent_sysvar: Pubkey,
+    pub clock_sysvar: Pubkey,
+    pub instructions_sysvar: Pubkey,
     pub system_program: Pubkey,
     pub token_program: Pubkey,
+    pub associated_token_program: Pubkey,
 }
 
 impl Default for IdConfig {
@@ -77,8 +86,11 @@ impl Default for IdConfig {
         IdConfig {
             program: Pubkey::from_str("GC2MzVrqKfnE8RArGMWVNgVx64qzQF85QrFJFkR5XoaP").unwrap(),
             rent_sysvar: solana_sdk::sysvar::rent::id(),
+            clock_sysvar: solana_sdk::sysvar::clock::id(),
+            instructions_sysvar: solana_sdk::sysvar::instructions::id(),
             system_program: solana_sdk::system_program::id(),
             token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
         }
     }
 }
\ No newline at end of file
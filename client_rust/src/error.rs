@@ -11,6 +11,13 @@ pub enum AirdropPoolClientError {
     },
     RpcClientError,
     ReferrerDidNotClaim,
+    VoucherRequired,
+    GateRequirementNotMet,
+    SelfReferral,
+    ReferralCycle,
+    InvalidRecipient {
+        line: usize,
+    },
 }
 
 impl Error for AirdropPoolClientError {
@@ -64,7 +64,7 @@ async fn release_initialize() {
     println!("release_initialize: max_referral_depth={:?}", max_referral_depth);
 
     let payer_id = Pubkey::from_str("DkmfiWSC4mnPvfMXZY2CkT4skvFkGr4u5DwRX2htRvJ2").unwrap();
-    let payer_token_account_id = program_config::get_claimer_token_account(&token_mint_id, &payer_id);
+    let payer_token_account_id = program_config::get_claimer_token_account(&spl_token::id(), &token_mint_id, &payer_id);
     println!("release_initialize: payer_id={:?}", payer_id);
     println!("release_initialize: payer_token_account_id={:?}", payer_token_account_id);
 
@@ -92,7 +92,14 @@ async fn release_initialize() {
     // //                     pool_account_nonce,
     // //                     reward_per_account,
     // //                     reward_per_referral,
-    // //                     max_referral_depth)
+    // //                     max_referral_depth,
+    // //                     None,
+    // //                     None,
+    // //                     None,
+    // //                     None,
+    // //                     None,
+    // //                     None,
+    // //                     vec![])
     // //     .unwrap();
     //
     // let airdrop_pool = config.rpc_client.get_account(&pool_account_id).unwrap();
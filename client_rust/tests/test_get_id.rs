@@ -22,7 +22,7 @@ async fn test_get_id() {
     let (pool_account_id, _pool_account_bump_seed) = program_config::get_pool_account(&program_id, &token_mint_id, &pool_account_nonce);
     let (pool_token_account_id, _pool_token_account_bump_seed) = program_config::get_pool_token_account(&program_id, &pool_account_id);
     let (claimer_account_id, _claimer_account_bump_seed) = program_config::get_claimer_account(&program_id, &pool_account_id, &claimer_wallet_id);
-    let claimer_token_account_id = program_config::get_claimer_token_account(&token_mint_id, &claimer_wallet_id);
+    let claimer_token_account_id = program_config::get_claimer_token_account(&spl_token::id(), &token_mint_id, &claimer_wallet_id);
 
     assert_eq!(pool_account_id, Pubkey::from_str("25sXXVsBY5Qx5QQ5w8563BmqibgkjwHBvKDBVFP52dCQ").unwrap());
     assert_eq!(pool_token_account_id, Pubkey::from_str("7NbJf1oXinHBYq3BF528xcUUmQ9786G8xZZFAB5jGe58").unwrap());
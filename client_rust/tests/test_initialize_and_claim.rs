@@ -4,6 +4,7 @@ use std::thread::sleep;
 use std::time::Duration;
 
 use chikin_airdrop_pool::config as program_config;
+use chikin_airdrop_pool::instruction::InitializeArgs;
 use chikin_airdrop_pool::packable::Packable;
 use chikin_airdrop_pool::state::{AirdropClaimer, AirdropPool};
 use solana_client::client_error::ClientError;
@@ -82,10 +83,13 @@ async fn test_initialize_and_claim() {
     println!("test_initialize: create_pool");
     command::initialize(&config,
                         test_token.mint.pubkey(),
-                        pool_account_nonce,
-                        reward_per_account,
-                        reward_per_referral,
-                        max_referral_depth)
+                        InitializeArgs {
+                            pool_account_nonce,
+                            reward_per_account,
+                            reward_per_referral,
+                            max_referral_depth,
+                            ..InitializeArgs::default()
+                        })
         .unwrap();
 
     let airdrop_pool = config.rpc_client.get_account(&pool_account_id).unwrap();
@@ -118,7 +122,7 @@ async fn test_initialize_and_claim() {
     testutil::debug_token_account("test_initialize: test_claimer_1.token_account before", &config, &test_claimer_1.token_account);
     testutil::debug_token_account("test_initialize: pool_token_account before", &config, &pool_token_account_id);
 
-    command::claim(&config, test_token.mint.pubkey(), pool_account_id, &test_claimer_1.wallet, None).unwrap();
+    command::claim(&config, test_token.mint.pubkey(), pool_account_id, &test_claimer_1.wallet, None, None).unwrap();
 
     testutil::debug_token_account("test_initialize: test_claimer_1.token_account after ", &config, &test_claimer_1.token_account);
     testutil::debug_token_account("test_initialize: pool_token_account after", &config, &pool_token_account_id);
@@ -133,7 +137,7 @@ async fn test_initialize_and_claim() {
     testutil::debug_token_account("test_initialize: test_claimer_2.token_account before", &config, &test_claimer_2.token_account);
     testutil::debug_token_account("test_initialize: pool_token_account before", &config, &pool_token_account_id);
 
-    command::claim(&config, test_token.mint.pubkey(), pool_account_id, &test_claimer_2.wallet, Some(test_claimer_1.wallet.pubkey())).unwrap();
+    command::claim(&config, test_token.mint.pubkey(), pool_account_id, &test_claimer_2.wallet, Some(test_claimer_1.wallet.pubkey()), None).unwrap();
 
     testutil::debug_token_account("test_initialize: test_claimer_2.token_account after ", &config, &test_claimer_2.token_account);
     testutil::debug_token_account("test_initialize: pool_token_account after", &config, &pool_token_account_id);
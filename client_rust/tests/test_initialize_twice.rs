@@ -4,6 +4,7 @@ use std::thread::sleep;
 use std::time::Duration;
 
 use chikin_airdrop_pool::config as program_config;
+use chikin_airdrop_pool::instruction::InitializeArgs;
 use chikin_airdrop_pool::packable::Packable;
 use chikin_airdrop_pool::state::{AirdropClaimer, AirdropPool};
 use solana_client::client_error::ClientError;
@@ -82,10 +83,13 @@ async fn test_initialize_and_claim() {
     println!("test_initialize: create_pool");
     command::initialize(&config,
                         test_token.mint.pubkey(),
-                        pool_account_nonce,
-                        reward_per_account,
-                        reward_per_referral,
-                        max_referral_depth)
+                        InitializeArgs {
+                            pool_account_nonce,
+                            reward_per_account,
+                            reward_per_referral,
+                            max_referral_depth,
+                            ..InitializeArgs::default()
+                        })
         .unwrap();
 
     let airdrop_pool = config.rpc_client.get_account(&pool_account_id).unwrap();
@@ -107,9 +111,12 @@ async fn test_initialize_and_claim() {
     // Initialize pool a second time
     command::initialize(&config,
                         test_token.mint.pubkey(),
-                        pool_account_nonce,
-                        reward_per_account,
-                        reward_per_referral,
-                        max_referral_depth)
+                        InitializeArgs {
+                            pool_account_nonce,
+                            reward_per_account,
+                            reward_per_referral,
+                            max_referral_depth,
+                            ..InitializeArgs::default()
+                        })
         .unwrap_err();
 }
\ No newline at end of file
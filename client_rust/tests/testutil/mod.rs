@@ -7,7 +7,6 @@ use solana_sdk::account::ReadableAccount;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
-use solana_sdk::transaction::Transaction;
 use spl_associated_token_account;
 use spl_token::state::Account as SplTokenAccount;
 
@@ -36,23 +35,7 @@ impl TestClaimer {
             sleep(Duration::from_millis(500));
         }
 
-        // Create token account
-        let mut transaction = Transaction::new_with_payer(
-            &vec![
-                spl_associated_token_account::create_associated_token_account(
-                    &wallet.pubkey(),
-                    &wallet.pubkey(),
-                    token_mint),
-            ],
-            Some(&wallet.pubkey()),
-        );
-        let mut signers = vec![&wallet];
-        let (recent_blockhash, _) = config.rpc_client.get_recent_blockhash().unwrap();
-        config.check_fee_payer_balance(1).unwrap(); // TODO
-        signers.sort_by_key(|e| e.pubkey());
-        signers.dedup();
-        transaction.sign(&signers, recent_blockhash);
-        config.send_transaction(transaction).unwrap();
+        // The token account is created by the first claim
         let token_account = spl_associated_token_account::get_associated_token_address(&wallet.pubkey(), &token_mint);
 
         TestClaimer { wallet, token_account }
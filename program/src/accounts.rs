@@ -0,0 +1,1360 @@
+//! Accounts of each instruction
+//!
+//! Each instruction parses its accounts into a struct, which checks their keys, owners, signers,
+//! writability and token mints. Handlers only validate the pool state and the instruction parameters.
+
+use solana_program::account_info::{AccountInfo, next_account_info, next_account_infos};
+use solana_program::bpf_loader_upgradeable;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+use crate::config;
+use crate::error::AirdropPoolError;
+use crate::packable::Packable;
+use crate::state::{AirdropClaimer, AirdropPool, AirdropPoolV0, RewardMint};
+use crate::token;
+
+pub struct InitializeAccounts<'a, 'b> {
+    pub funder: &'a AccountInfo<'b>,
+    pub program: &'a AccountInfo<'b>,
+    pub rent_sysvar: &'a AccountInfo<'b>,
+    pub system_program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub token_mint: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_token_account: &'a AccountInfo<'b>,
+    pub pool_account_bump_seed: u8,
+    pub pool_token_account_bump_seed: u8,
+}
+
+impl<'a, 'b> InitializeAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>],
+                 pool_account_nonce: &[u8; 4]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let funder = next_account_info(accounts_iter)?;
+        let program = next_account_info(accounts_iter)?;
+        let rent_sysvar = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let token_mint = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let pool_token_account = next_account_info(accounts_iter)?;
+
+        //
+
+        let (pool_account_id, pool_account_bump_seed) = config::get_pool_account(program.key, token_mint.key, pool_account_nonce);
+        let (pool_token_account_id, pool_token_account_bump_seed) = config::get_pool_token_account(program.key, pool_account.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_rent_sysvar(rent_sysvar)?;
+        check_system_program(system_program)?;
+        // The system program initializes a native SOL pool
+        if !token::is_token_program(token_program.key) && !token::is_native(token_program.key) {
+            return Err(AirdropPoolError::TokenProgramKeyMismatch.into());
+        }
+        if pool_account.key != &pool_account_id {
+            return Err(AirdropPoolError::PoolAccountKeyMismatch.into());
+        }
+        if pool_token_account.key != &pool_token_account_id {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+
+        // Validate owners, signers and writability
+
+        check_token_mint(token_program, token_mint)?;
+        if !funder.is_signer {
+            return Err(AirdropPoolError::FunderDidNotSign.into());
+        }
+        check_writable(&[funder, pool_account, pool_token_account])?;
+
+        Ok(InitializeAccounts {
+            funder,
+            program,
+            rent_sysvar,
+            system_program,
+            token_program,
+            token_mint,
+            pool_account,
+            pool_token_account,
+            pool_account_bump_seed,
+            pool_token_account_bump_seed,
+        })
+    }
+}
+
+// Claim and ClaimWithProof, the referrer claimer accounts are checked while walking the referral chain.
+// Token gated pools take the gate token account of the claimer, then pools charging a claim fee take
+// the fee recipient, then pools with reward mints take the accounts of each reward mint, before the
+// referrer claimer accounts. The payer may be the claimer wallet.
+// The sysvars and the associated token program are missing from the accounts of legacy claims.
+pub struct ClaimAccounts<'a, 'b> {
+    pub payer: &'a AccountInfo<'b>,
+    pub program: &'a AccountInfo<'b>,
+    pub rent_sysvar: &'a AccountInfo<'b>,
+    pub clock_sysvar: Option<&'a AccountInfo<'b>>,
+    pub instructions_sysvar: Option<&'a AccountInfo<'b>>,
+    pub system_program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub associated_token_program: Option<&'a AccountInfo<'b>>,
+    pub token_mint: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_token_account: &'a AccountInfo<'b>,
+    pub claimer_wallet: &'a AccountInfo<'b>,
+    pub claimer_account: &'a AccountInfo<'b>,
+    pub claimer_token_account: &'a AccountInfo<'b>,
+    pub gate_token_account: Option<&'a AccountInfo<'b>>,
+    pub fee_recipient: Option<&'a AccountInfo<'b>>,
+    pub reward_mint_accounts: Vec<RewardMintAccounts<'a, 'b>>,
+    pub referrer_accounts: Vec<&'a AccountInfo<'b>>,
+    pub pool_account_state: AirdropPool,
+    pub pool_account_bump_seed: u8,
+    pub claimer_account_bump_seed: u8,
+}
+
+impl<'a, 'b> ClaimAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        Self::parse_layout(program_id, accounts, false)
+    }
+
+    // Claim of deployed clients, whose accounts are [program, rent sysvar, system program, token program,
+    // token mint, pool, pool token account, claimer wallet, claimer account, claimer token account] then
+    // the wallet, claimer account and token account of each referrer. The claimer wallet pays, and pools
+    // requiring accounts which deployed clients don't send reject the claim.
+    pub fn parse_legacy(program_id: &Pubkey,
+                        accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        Self::parse_layout(program_id, accounts, true)
+    }
+
+    fn parse_layout(program_id: &Pubkey,
+                    accounts: &'a [AccountInfo<'b>],
+                    is_legacy: bool) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let payer = if is_legacy { None } else { Some(next_account_info(accounts_iter)?) };
+        let program = next_account_info(accounts_iter)?;
+        let rent_sysvar = next_account_info(accounts_iter)?;
+        let clock_sysvar = if is_legacy { None } else { Some(next_account_info(accounts_iter)?) };
+        let instructions_sysvar = if is_legacy { None } else { Some(next_account_info(accounts_iter)?) };
+        let system_program = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let associated_token_program = if is_legacy { None } else { Some(next_account_info(accounts_iter)?) };
+        let token_mint = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let pool_token_account = next_account_info(accounts_iter)?;
+        let claimer_wallet = next_account_info(accounts_iter)?;
+        let claimer_account = next_account_info(accounts_iter)?;
+        let claimer_token_account = next_account_info(accounts_iter)?;
+        let payer = payer.unwrap_or(claimer_wallet);
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+
+        //
+
+        if is_legacy && (pool_account_state.gate_mint.is_some()
+            || pool_account_state.get_fee_recipient().is_some()
+            || !pool_account_state.get_reward_mints().is_empty()
+            || pool_account_state.voucher_signer.is_some()) {
+            return Err(AirdropPoolError::LegacyClaimNotSupported.into());
+        }
+        let gate_token_account = match pool_account_state.gate_mint {
+            Some(_) => Some(next_account_info(accounts_iter).map_err(|_| AirdropPoolError::GateRequirementNotMet)?),
+            None => None,
+        };
+        let fee_recipient = match pool_account_state.get_fee_recipient() {
+            Some(_) => Some(next_account_info(accounts_iter)?),
+            None => None,
+        };
+        let reward_mint_account_list = next_account_infos(accounts_iter, 3 * pool_account_state.get_reward_mints().len())?;
+        let referrer_accounts = if is_legacy {
+            // Referrers withdraw their rewards, only their claimer accounts are used
+            accounts_iter.as_slice().chunks(3).filter_map(|referrer_account_list| referrer_account_list.get(1)).collect()
+        } else {
+            accounts_iter.as_slice().iter().collect()
+        };
+
+        //
+
+        let (pool_account_id, pool_account_bump_seed) = config::get_pool_account(program.key, token_mint.key, &pool_account_state.account_nonce);
+        let (pool_token_account_id, _) = config::get_pool_token_account(program.key, pool_account.key);
+        let (claimer_account_id, claimer_account_bump_seed) = config::get_claimer_account(program.key, pool_account.key, claimer_wallet.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_rent_sysvar(rent_sysvar)?;
+        if let Some(clock_sysvar) = clock_sysvar {
+            check_clock_sysvar(clock_sysvar)?;
+        }
+        if let Some(instructions_sysvar) = instructions_sysvar {
+            if instructions_sysvar.key != &solana_program::sysvar::instructions::id() {
+                return Err(AirdropPoolError::InstructionsSysvarKeyMismatch.into());
+            }
+        }
+        check_system_program(system_program)?;
+        if let Some(associated_token_program) = associated_token_program {
+            if associated_token_program.key != &spl_associated_token_account::id() {
+                return Err(AirdropPoolError::AssociatedTokenProgramKeyMismatch.into());
+            }
+        }
+        check_pool_account(pool_account, &pool_account_id, program_id)?;
+        check_token_program(token_program, &pool_account_state)?;
+        if pool_token_account.key != &pool_token_account_id {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        if claimer_account.key != &claimer_account_id {
+            return Err(AirdropPoolError::UserAccountKeyMismatch.into());
+        }
+        if claimer_token_account.key != &config::get_claimer_token_account(token_program.key, token_mint.key, claimer_wallet.key) {
+            return Err(AirdropPoolError::UserTokenAccountKeyMismatch.into());
+        }
+        if let Some(fee_recipient) = fee_recipient {
+            if fee_recipient.key != &pool_account_state.fee_recipient {
+                return Err(AirdropPoolError::FeeRecipientKeyMismatch.into());
+            }
+        }
+        let reward_mint_accounts = parse_reward_mint_accounts(program,
+                                                              token_program,
+                                                              pool_account,
+                                                              &pool_account_state,
+                                                              claimer_wallet.key,
+                                                              reward_mint_account_list,
+                                                              AirdropPoolError::UserTokenAccountKeyMismatch)?;
+
+        // Validate owners, signers and writability
+
+        check_token_mint(token_program, token_mint)?;
+        // The claim creates the claimer token account when it does not exist yet
+        if !claimer_token_account.data_is_empty() {
+            check_token_account(token_program,
+                                token_mint,
+                                claimer_token_account,
+                                Some(claimer_wallet.key),
+                                AirdropPoolError::UserTokenAccountMintMismatch,
+                                AirdropPoolError::UserTokenAccountOwnerMismatch)?;
+        }
+        if !payer.is_signer {
+            return Err(AirdropPoolError::FunderDidNotSign.into());
+        }
+        if !claimer_wallet.is_signer {
+            return Err(AirdropPoolError::ClaimerDidNotSign.into());
+        }
+        check_writable(&[payer, pool_account, pool_token_account, claimer_wallet, claimer_account, claimer_token_account])?;
+        if let Some(fee_recipient) = fee_recipient {
+            check_writable(&[fee_recipient])?;
+        }
+        for reward_mint_account in &reward_mint_accounts {
+            // Created by the claim as well
+            if !reward_mint_account.token_account.data_is_empty() {
+                check_token_account(token_program,
+                                    reward_mint_account.token_mint,
+                                    reward_mint_account.token_account,
+                                    Some(claimer_wallet.key),
+                                    AirdropPoolError::UserTokenAccountMintMismatch,
+                                    AirdropPoolError::UserTokenAccountOwnerMismatch)?;
+            }
+        }
+
+        Ok(ClaimAccounts {
+            payer,
+            program,
+            rent_sysvar,
+            clock_sysvar,
+            instructions_sysvar,
+            system_program,
+            token_program,
+            associated_token_program,
+            token_mint,
+            pool_account,
+            pool_token_account,
+            claimer_wallet,
+            claimer_account,
+            claimer_token_account,
+            gate_token_account,
+            fee_recipient,
+            reward_mint_accounts,
+            referrer_accounts,
+            pool_account_state,
+            pool_account_bump_seed,
+            claimer_account_bump_seed,
+        })
+    }
+}
+
+pub struct ReleaseAccounts<'a, 'b> {
+    pub program: &'a AccountInfo<'b>,
+    pub clock_sysvar: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub token_mint: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_token_account: &'a AccountInfo<'b>,
+    pub claimer_wallet: &'a AccountInfo<'b>,
+    pub claimer_account: &'a AccountInfo<'b>,
+    pub claimer_token_account: &'a AccountInfo<'b>,
+    pub pool_account_state: AirdropPool,
+    pub pool_account_bump_seed: u8,
+}
+
+impl<'a, 'b> ReleaseAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let program = next_account_info(accounts_iter)?;
+        let clock_sysvar = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let token_mint = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let pool_token_account = next_account_info(accounts_iter)?;
+        let claimer_wallet = next_account_info(accounts_iter)?;
+        let claimer_account = next_account_info(accounts_iter)?;
+        let claimer_token_account = next_account_info(accounts_iter)?;
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+
+        //
+
+        let (pool_account_id, pool_account_bump_seed) = config::get_pool_account(program.key, token_mint.key, &pool_account_state.account_nonce);
+        let (pool_token_account_id, _) = config::get_pool_token_account(program.key, pool_account.key);
+        let (claimer_account_id, _) = config::get_claimer_account(program.key, pool_account.key, claimer_wallet.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_clock_sysvar(clock_sysvar)?;
+        check_pool_account(pool_account, &pool_account_id, program_id)?;
+        check_token_program(token_program, &pool_account_state)?;
+        if pool_token_account.key != &pool_token_account_id {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        if claimer_account.key != &claimer_account_id {
+            return Err(AirdropPoolError::UserAccountKeyMismatch.into());
+        }
+        if claimer_token_account.key != &config::get_claimer_token_account(token_program.key, token_mint.key, claimer_wallet.key) {
+            return Err(AirdropPoolError::UserTokenAccountKeyMismatch.into());
+        }
+
+        // Validate owners and writability, anyone can release the vested rewards of a claimer
+
+        check_token_mint(token_program, token_mint)?;
+        if claimer_account.owner != program_id {
+            return Err(AirdropPoolError::UserAccountOwnerMismatch.into());
+        }
+        check_token_account(token_program,
+                            token_mint,
+                            claimer_token_account,
+                            Some(claimer_wallet.key),
+                            AirdropPoolError::UserTokenAccountMintMismatch,
+                            AirdropPoolError::UserTokenAccountOwnerMismatch)?;
+        check_writable(&[pool_account, pool_token_account, claimer_account, claimer_token_account])?;
+
+        Ok(ReleaseAccounts {
+            program,
+            clock_sysvar,
+            token_program,
+            token_mint,
+            pool_account,
+            pool_token_account,
+            claimer_wallet,
+            claimer_account,
+            claimer_token_account,
+            pool_account_state,
+            pool_account_bump_seed,
+        })
+    }
+}
+
+pub struct WithdrawReferralRewardsAccounts<'a, 'b> {
+    pub program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub token_mint: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_token_account: &'a AccountInfo<'b>,
+    pub referrer_wallet: &'a AccountInfo<'b>,
+    pub referrer_account: &'a AccountInfo<'b>,
+    pub referrer_token_account: &'a AccountInfo<'b>,
+    pub reward_mint_accounts: Vec<RewardMintAccounts<'a, 'b>>,
+    pub pool_account_state: AirdropPool,
+    pub pool_account_bump_seed: u8,
+}
+
+impl<'a, 'b> WithdrawReferralRewardsAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let program = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let token_mint = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let pool_token_account = next_account_info(accounts_iter)?;
+        let referrer_wallet = next_account_info(accounts_iter)?;
+        let referrer_account = next_account_info(accounts_iter)?;
+        let referrer_token_account = next_account_info(accounts_iter)?;
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+
+        //
+
+        let reward_mint_account_list = next_account_infos(accounts_iter, 3 * pool_account_state.get_reward_mints().len())?;
+
+        let (pool_account_id, pool_account_bump_seed) = config::get_pool_account(program.key, token_mint.key, &pool_account_state.account_nonce);
+        let (pool_token_account_id, _) = config::get_pool_token_account(program.key, pool_account.key);
+        let (referrer_account_id, _) = config::get_claimer_account(program.key, pool_account.key, referrer_wallet.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_pool_account(pool_account, &pool_account_id, program_id)?;
+        check_token_program(token_program, &pool_account_state)?;
+        if pool_token_account.key != &pool_token_account_id {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        if referrer_account.key != &referrer_account_id {
+            return Err(AirdropPoolError::ReferrerAccountKeyMismatch.into());
+        }
+        if referrer_token_account.key != &config::get_claimer_token_account(token_program.key, token_mint.key, referrer_wallet.key) {
+            return Err(AirdropPoolError::ReferrerTokenAccountKeyMismatch.into());
+        }
+        let reward_mint_accounts = parse_reward_mint_accounts(program,
+                                                              token_program,
+                                                              pool_account,
+                                                              &pool_account_state,
+                                                              referrer_wallet.key,
+                                                              reward_mint_account_list,
+                                                              AirdropPoolError::ReferrerTokenAccountKeyMismatch)?;
+
+        // Validate owners and writability, anyone can send the referral rewards of a referrer
+
+        check_token_mint(token_program, token_mint)?;
+        if referrer_account.owner != program_id {
+            return Err(AirdropPoolError::UserAccountOwnerMismatch.into());
+        }
+        check_token_account(token_program,
+                            token_mint,
+                            referrer_token_account,
+                            Some(referrer_wallet.key),
+                            AirdropPoolError::ReferrerTokenAccountMintMismatch,
+                            AirdropPoolError::ReferrerTokenAccountOwnerMismatch)?;
+        check_writable(&[pool_account, pool_token_account, referrer_account, referrer_token_account])?;
+        // Referrers may have no token account for a reward mint they have no rewards of
+        for reward_mint_account in &reward_mint_accounts {
+            if !reward_mint_account.token_account.data_is_empty() {
+                check_token_account(token_program,
+                                    reward_mint_account.token_mint,
+                                    reward_mint_account.token_account,
+                                    Some(referrer_wallet.key),
+                                    AirdropPoolError::ReferrerTokenAccountMintMismatch,
+                                    AirdropPoolError::ReferrerTokenAccountOwnerMismatch)?;
+            }
+        }
+
+        Ok(WithdrawReferralRewardsAccounts {
+            program,
+            token_program,
+            token_mint,
+            pool_account,
+            pool_token_account,
+            referrer_wallet,
+            referrer_account,
+            referrer_token_account,
+            reward_mint_accounts,
+            pool_account_state,
+            pool_account_bump_seed,
+        })
+    }
+}
+
+pub struct DepositAccounts<'a, 'b> {
+    pub depositor_wallet: &'a AccountInfo<'b>,
+    pub program: &'a AccountInfo<'b>,
+    pub rent_sysvar: &'a AccountInfo<'b>,
+    pub system_program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub token_mint: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_token_account: &'a AccountInfo<'b>,
+    pub depositor_token_account: &'a AccountInfo<'b>,
+    pub depositor_account: &'a AccountInfo<'b>,
+    pub pool_account_state: AirdropPool,
+    pub depositor_account_bump_seed: u8,
+}
+
+impl<'a, 'b> DepositAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let depositor_wallet = next_account_info(accounts_iter)?;
+        let program = next_account_info(accounts_iter)?;
+        let rent_sysvar = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let token_mint = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let pool_token_account = next_account_info(accounts_iter)?;
+        let depositor_token_account = next_account_info(accounts_iter)?;
+        let depositor_account = next_account_info(accounts_iter)?;
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+
+        //
+
+        let (pool_account_id, _) = config::get_pool_account(program.key, token_mint.key, &pool_account_state.account_nonce);
+        let (pool_token_account_id, _) = config::get_pool_token_account(program.key, pool_account.key);
+        let (depositor_account_id, depositor_account_bump_seed) = config::get_depositor_account(program.key, pool_account.key, depositor_wallet.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_rent_sysvar(rent_sysvar)?;
+        check_system_program(system_program)?;
+        check_pool_account(pool_account, &pool_account_id, program_id)?;
+        check_token_program(token_program, &pool_account_state)?;
+        if pool_token_account.key != &pool_token_account_id {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        if depositor_account.key != &depositor_account_id {
+            return Err(AirdropPoolError::DepositorAccountKeyMismatch.into());
+        }
+
+        // Validate owners, signers and writability, the token program checks the depositor token account authority
+
+        check_token_mint(token_program, token_mint)?;
+        check_token_account(token_program,
+                            token_mint,
+                            depositor_token_account,
+                            None,
+                            AirdropPoolError::DepositorTokenAccountMintMismatch,
+                            AirdropPoolError::DepositorTokenAccountOwnerMismatch)?;
+        if !depositor_wallet.is_signer {
+            return Err(AirdropPoolError::DepositorDidNotSign.into());
+        }
+        check_writable(&[depositor_wallet, pool_account, pool_token_account, depositor_token_account, depositor_account])?;
+
+        Ok(DepositAccounts {
+            depositor_wallet,
+            program,
+            rent_sysvar,
+            system_program,
+            token_program,
+            token_mint,
+            pool_account,
+            pool_token_account,
+            depositor_token_account,
+            depositor_account,
+            pool_account_state,
+            depositor_account_bump_seed,
+        })
+    }
+}
+
+// Claimers to migrate follow the pool account as (claimer wallet, claimer account) pairs
+// Pools created before the authority take the ProgramData account of the program and its upgrade authority,
+// which becomes their authority, before the claimer accounts
+pub struct MigratePoolAccounts<'a, 'b> {
+    pub payer: &'a AccountInfo<'b>,
+    pub program: &'a AccountInfo<'b>,
+    pub system_program: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub program_data: Option<&'a AccountInfo<'b>>,
+    pub upgrade_authority: Option<&'a AccountInfo<'b>>,
+    pub claimer_accounts: Vec<&'a AccountInfo<'b>>,
+    pub pool_account_state: AirdropPool,
+    pub is_pool_migrated: bool,
+}
+
+impl<'a, 'b> MigratePoolAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let payer = next_account_info(accounts_iter)?;
+        let program = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let (program_data, upgrade_authority) = if pool_account.data_len() == AirdropPoolV0::PACKED_SIZE {
+            (Some(next_account_info(accounts_iter)?), Some(next_account_info(accounts_iter)?))
+        } else {
+            (None, None)
+        };
+        let claimer_accounts_with_wallets = accounts_iter.as_slice();
+        if !claimer_accounts_with_wallets.len().is_multiple_of(2) {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_system_program(system_program)?;
+        if pool_account.owner != program_id {
+            return Err(AirdropPoolError::PoolAccountOwnerMismatch.into());
+        }
+        if let (Some(program_data), Some(upgrade_authority)) = (program_data, upgrade_authority) {
+            check_upgrade_authority(program, program_data, upgrade_authority)?;
+        }
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+        let is_pool_migrated = AirdropPool::is_current_layout(*pool_account.data.borrow());
+
+        //
+
+        let (pool_account_id, _) = config::get_pool_account(program.key, &pool_account_state.token_mint_id, &pool_account_state.account_nonce);
+        if pool_account.key != &pool_account_id {
+            return Err(AirdropPoolError::PoolAccountKeyMismatch.into());
+        }
+        if is_pool_migrated && claimer_accounts_with_wallets.is_empty() {
+            return Err(AirdropPoolError::PoolAlreadyMigrated.into());
+        }
+
+        let mut claimer_accounts = Vec::with_capacity(claimer_accounts_with_wallets.len() / 2);
+        for claimer_account_with_wallet in claimer_accounts_with_wallets.chunks(2) {
+            let claimer_wallet = &claimer_account_with_wallet[0];
+            let claimer_account = &claimer_account_with_wallet[1];
+            if claimer_account.key != &config::get_claimer_account(program.key, pool_account.key, claimer_wallet.key).0 {
+                return Err(AirdropPoolError::UserAccountKeyMismatch.into());
+            }
+            if claimer_account.owner != program_id {
+                return Err(AirdropPoolError::UserAccountOwnerMismatch.into());
+            }
+            if AirdropClaimer::is_current_layout(*claimer_account.data.borrow()) {
+                return Err(AirdropPoolError::ClaimerAlreadyMigrated.into());
+            }
+            claimer_accounts.push(claimer_account);
+        }
+
+        // Validate signers and writability
+
+        if !payer.is_signer {
+            return Err(AirdropPoolError::FunderDidNotSign.into());
+        }
+        check_writable(&[payer, pool_account])?;
+        check_writable(&claimer_accounts)?;
+
+        Ok(MigratePoolAccounts {
+            payer,
+            program,
+            system_program,
+            pool_account,
+            program_data,
+            upgrade_authority,
+            claimer_accounts,
+            pool_account_state,
+            is_pool_migrated,
+        })
+    }
+}
+
+// The pool account may already be closed, so it is not checked
+pub struct CloseClaimerAccounts<'a, 'b> {
+    pub program: &'a AccountInfo<'b>,
+    pub clock_sysvar: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub claimer_wallet: &'a AccountInfo<'b>,
+    pub claimer_account: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> CloseClaimerAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let program = next_account_info(accounts_iter)?;
+        let clock_sysvar = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let claimer_wallet = next_account_info(accounts_iter)?;
+        let claimer_account = next_account_info(accounts_iter)?;
+
+        //
+
+        let (claimer_account_id, _) = config::get_claimer_account(program.key, pool_account.key, claimer_wallet.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_clock_sysvar(clock_sysvar)?;
+        if claimer_account.key != &claimer_account_id {
+            return Err(AirdropPoolError::UserAccountKeyMismatch.into());
+        }
+
+        // Validate owners, signers and writability
+
+        if claimer_account.owner != program_id {
+            return Err(AirdropPoolError::UserAccountOwnerMismatch.into());
+        }
+        if !claimer_wallet.is_signer {
+            return Err(AirdropPoolError::ClaimerDidNotSign.into());
+        }
+        check_writable(&[claimer_wallet, claimer_account])?;
+
+        Ok(CloseClaimerAccounts {
+            program,
+            clock_sysvar,
+            pool_account,
+            claimer_wallet,
+            claimer_account,
+        })
+    }
+}
+
+// UpdatePool, Pause and Resume
+pub struct UpdatePoolAccounts<'a, 'b> {
+    pub authority: &'a AccountInfo<'b>,
+    pub program: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_account_state: AirdropPool,
+}
+
+impl<'a, 'b> UpdatePoolAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let authority = next_account_info(accounts_iter)?;
+        let program = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+
+        //
+
+        let (pool_account_id, _) = config::get_pool_account(program.key, &pool_account_state.token_mint_id, &pool_account_state.account_nonce);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_pool_account(pool_account, &pool_account_id, program_id)?;
+        check_authority(authority, &pool_account_state)?;
+
+        // Validate writability
+
+        check_writable(&[pool_account])?;
+
+        Ok(UpdatePoolAccounts {
+            authority,
+            program,
+            pool_account,
+            pool_account_state,
+        })
+    }
+}
+
+pub struct AddRewardMintAccounts<'a, 'b> {
+    pub authority: &'a AccountInfo<'b>,
+    pub program: &'a AccountInfo<'b>,
+    pub rent_sysvar: &'a AccountInfo<'b>,
+    pub system_program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub reward_mint: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_reward_token_account: &'a AccountInfo<'b>,
+    pub pool_account_state: AirdropPool,
+    pub pool_reward_token_account_bump_seed: u8,
+}
+
+impl<'a, 'b> AddRewardMintAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let authority = next_account_info(accounts_iter)?;
+        let program = next_account_info(accounts_iter)?;
+        let rent_sysvar = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let reward_mint = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let pool_reward_token_account = next_account_info(accounts_iter)?;
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+
+        //
+
+        let (pool_account_id, _) = config::get_pool_account(program.key, &pool_account_state.token_mint_id, &pool_account_state.account_nonce);
+        let (pool_reward_token_account_id, pool_reward_token_account_bump_seed) = config::get_pool_reward_token_account(program.key, pool_account.key, reward_mint.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_rent_sysvar(rent_sysvar)?;
+        check_system_program(system_program)?;
+        check_pool_account(pool_account, &pool_account_id, program_id)?;
+        check_token_program(token_program, &pool_account_state)?;
+        if pool_reward_token_account.key != &pool_reward_token_account_id {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        check_authority(authority, &pool_account_state)?;
+        if token::is_native(token_program.key) {
+            return Err(AirdropPoolError::RewardMintsNotSupported.into());
+        }
+
+        // Validate owners and writability, reward mints use the token program of the pool
+
+        check_token_mint(token_program, reward_mint)?;
+        check_writable(&[authority, pool_account, pool_reward_token_account])?;
+
+        Ok(AddRewardMintAccounts {
+            authority,
+            program,
+            rent_sysvar,
+            system_program,
+            token_program,
+            reward_mint,
+            pool_account,
+            pool_reward_token_account,
+            pool_account_state,
+            pool_reward_token_account_bump_seed,
+        })
+    }
+}
+
+pub struct WithdrawAccounts<'a, 'b> {
+    pub authority: &'a AccountInfo<'b>,
+    pub program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub token_mint: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_token_account: &'a AccountInfo<'b>,
+    pub destination_token_account: &'a AccountInfo<'b>,
+    pub reward_mint_accounts: Vec<RewardMintAccounts<'a, 'b>>,
+    pub pool_account_state: AirdropPool,
+    pub pool_account_bump_seed: u8,
+}
+
+impl<'a, 'b> WithdrawAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let authority = next_account_info(accounts_iter)?;
+        let program = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let token_mint = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let pool_token_account = next_account_info(accounts_iter)?;
+        let destination_token_account = next_account_info(accounts_iter)?;
+        // The reward mints whose pool reward token accounts are emptied, optional
+        let reward_mint_account_list = accounts_iter.as_slice();
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+
+        //
+
+        let (pool_account_id, pool_account_bump_seed) = config::get_pool_account(program.key, token_mint.key, &pool_account_state.account_nonce);
+        let (pool_token_account_id, _) = config::get_pool_token_account(program.key, pool_account.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_pool_account(pool_account, &pool_account_id, program_id)?;
+        check_token_program(token_program, &pool_account_state)?;
+        if pool_token_account.key != &pool_token_account_id {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        check_authority(authority, &pool_account_state)?;
+        let reward_mint_accounts = parse_reward_vault_accounts(program,
+                                                               token_program,
+                                                               pool_account,
+                                                               &pool_account_state,
+                                                               reward_mint_account_list)?;
+
+        // Validate owners and writability
+
+        check_token_mint(token_program, token_mint)?;
+        check_token_account(token_program,
+                            token_mint,
+                            destination_token_account,
+                            None,
+                            AirdropPoolError::DestinationTokenAccountMintMismatch,
+                            AirdropPoolError::DestinationTokenAccountMintMismatch)?;
+        check_writable(&[pool_token_account, destination_token_account])?;
+        // Native SOL pools pay from the pool account itself
+        if token::is_native(token_program.key) {
+            check_writable(&[pool_account])?;
+        }
+
+        Ok(WithdrawAccounts {
+            authority,
+            program,
+            token_program,
+            token_mint,
+            pool_account,
+            pool_token_account,
+            destination_token_account,
+            reward_mint_accounts,
+            pool_account_state,
+            pool_account_bump_seed,
+        })
+    }
+}
+
+// Accounts of a Distribute recipient, the distribution creates its claimer account and its token account
+pub struct RecipientAccounts<'a, 'b> {
+    pub wallet: &'a AccountInfo<'b>,
+    pub claimer_account: &'a AccountInfo<'b>,
+    pub token_account: &'a AccountInfo<'b>,
+    pub claimer_account_bump_seed: u8,
+}
+
+// Distribute, the recipients are (wallet, claimer account, token account) triples
+pub struct DistributeAccounts<'a, 'b> {
+    pub authority: &'a AccountInfo<'b>,
+    pub program: &'a AccountInfo<'b>,
+    pub rent_sysvar: &'a AccountInfo<'b>,
+    pub clock_sysvar: &'a AccountInfo<'b>,
+    pub system_program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub associated_token_program: &'a AccountInfo<'b>,
+    pub token_mint: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_token_account: &'a AccountInfo<'b>,
+    pub recipients: Vec<RecipientAccounts<'a, 'b>>,
+    pub pool_account_state: AirdropPool,
+    pub pool_account_bump_seed: u8,
+}
+
+impl<'a, 'b> DistributeAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let authority = next_account_info(accounts_iter)?;
+        let program = next_account_info(accounts_iter)?;
+        let rent_sysvar = next_account_info(accounts_iter)?;
+        let clock_sysvar = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let associated_token_program = next_account_info(accounts_iter)?;
+        let token_mint = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let pool_token_account = next_account_info(accounts_iter)?;
+        let recipient_accounts = accounts_iter.as_slice();
+        if !recipient_accounts.len().is_multiple_of(3) {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+
+        //
+
+        let (pool_account_id, pool_account_bump_seed) = config::get_pool_account(program.key, token_mint.key, &pool_account_state.account_nonce);
+        let (pool_token_account_id, _) = config::get_pool_token_account(program.key, pool_account.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_rent_sysvar(rent_sysvar)?;
+        check_clock_sysvar(clock_sysvar)?;
+        check_system_program(system_program)?;
+        if associated_token_program.key != &spl_associated_token_account::id() {
+            return Err(AirdropPoolError::AssociatedTokenProgramKeyMismatch.into());
+        }
+        check_pool_account(pool_account, &pool_account_id, program_id)?;
+        check_token_program(token_program, &pool_account_state)?;
+        if pool_token_account.key != &pool_token_account_id {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        check_authority(authority, &pool_account_state)?;
+
+        let mut recipients = Vec::with_capacity(recipient_accounts.len() / 3);
+        for recipient_account in recipient_accounts.chunks(3) {
+            let wallet = &recipient_account[0];
+            let claimer_account = &recipient_account[1];
+            let token_account = &recipient_account[2];
+            let (claimer_account_id, claimer_account_bump_seed) = config::get_claimer_account(program.key, pool_account.key, wallet.key);
+            if claimer_account.key != &claimer_account_id {
+                return Err(AirdropPoolError::UserAccountKeyMismatch.into());
+            }
+            if token_account.key != &config::get_claimer_token_account(token_program.key, token_mint.key, wallet.key) {
+                return Err(AirdropPoolError::UserTokenAccountKeyMismatch.into());
+            }
+            recipients.push(RecipientAccounts {
+                wallet,
+                claimer_account,
+                token_account,
+                claimer_account_bump_seed,
+            });
+        }
+
+        // Validate owners and writability
+
+        check_token_mint(token_program, token_mint)?;
+        check_writable(&[authority, pool_account, pool_token_account])?;
+        for recipient in &recipients {
+            // Recipients which already claimed, or received a distribution, have a claimer account
+            if !recipient.claimer_account.data_is_empty() {
+                return Err(AirdropPoolError::AlreadyClaimed.into());
+            }
+            if !recipient.token_account.data_is_empty() {
+                check_token_account(token_program,
+                                    token_mint,
+                                    recipient.token_account,
+                                    Some(recipient.wallet.key),
+                                    AirdropPoolError::UserTokenAccountMintMismatch,
+                                    AirdropPoolError::UserTokenAccountOwnerMismatch)?;
+            }
+            check_writable(&[recipient.claimer_account, recipient.token_account])?;
+        }
+
+        Ok(DistributeAccounts {
+            authority,
+            program,
+            rent_sysvar,
+            clock_sysvar,
+            system_program,
+            token_program,
+            associated_token_program,
+            token_mint,
+            pool_account,
+            pool_token_account,
+            recipients,
+            pool_account_state,
+            pool_account_bump_seed,
+        })
+    }
+}
+
+pub struct ClosePoolAccounts<'a, 'b> {
+    pub authority: &'a AccountInfo<'b>,
+    pub program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub token_mint: &'a AccountInfo<'b>,
+    pub pool_account: &'a AccountInfo<'b>,
+    pub pool_token_account: &'a AccountInfo<'b>,
+    pub destination_token_account: &'a AccountInfo<'b>,
+    pub destination_wallet: &'a AccountInfo<'b>,
+    pub reward_mint_accounts: Vec<RewardMintAccounts<'a, 'b>>,
+    pub pool_account_state: AirdropPool,
+    pub pool_account_bump_seed: u8,
+}
+
+impl<'a, 'b> ClosePoolAccounts<'a, 'b> {
+    pub fn parse(program_id: &Pubkey,
+                 accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let authority = next_account_info(accounts_iter)?;
+        let program = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let token_mint = next_account_info(accounts_iter)?;
+        let pool_account = next_account_info(accounts_iter)?;
+        let pool_token_account = next_account_info(accounts_iter)?;
+        let destination_token_account = next_account_info(accounts_iter)?;
+        let destination_wallet = next_account_info(accounts_iter)?;
+
+        // Unpack states
+
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+
+        // Every pool reward token account is closed along with the pool
+        let reward_mint_account_list = next_account_infos(accounts_iter, 3 * pool_account_state.get_reward_mints().len())?;
+
+        //
+
+        let (pool_account_id, pool_account_bump_seed) = config::get_pool_account(program.key, token_mint.key, &pool_account_state.account_nonce);
+        let (pool_token_account_id, _) = config::get_pool_token_account(program.key, pool_account.key);
+
+        // Validate keys
+
+        check_program(program, program_id)?;
+        check_pool_account(pool_account, &pool_account_id, program_id)?;
+        check_token_program(token_program, &pool_account_state)?;
+        if pool_token_account.key != &pool_token_account_id {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        check_authority(authority, &pool_account_state)?;
+        let reward_mint_accounts = parse_reward_vault_accounts(program,
+                                                               token_program,
+                                                               pool_account,
+                                                               &pool_account_state,
+                                                               reward_mint_account_list)?;
+
+        // Validate owners and writability
+
+        check_token_mint(token_program, token_mint)?;
+        check_token_account(token_program,
+                            token_mint,
+                            destination_token_account,
+                            None,
+                            AirdropPoolError::DestinationTokenAccountMintMismatch,
+                            AirdropPoolError::DestinationTokenAccountMintMismatch)?;
+        check_writable(&[pool_account, pool_token_account, destination_token_account, destination_wallet])?;
+
+        Ok(ClosePoolAccounts {
+            authority,
+            program,
+            token_program,
+            token_mint,
+            pool_account,
+            pool_token_account,
+            destination_token_account,
+            destination_wallet,
+            reward_mint_accounts,
+            pool_account_state,
+            pool_account_bump_seed,
+        })
+    }
+}
+
+// Claimer account of the next referrer of the referral chain, which the claim credits
+pub fn check_referrer_account(program_id: &Pubkey,
+                              pool_account: &AccountInfo,
+                              referrer_account: &AccountInfo,
+                              referrer_wallet: &Pubkey) -> ProgramResult {
+    if referrer_account.key != &config::get_claimer_account(program_id, pool_account.key, referrer_wallet).0 {
+        return Err(AirdropPoolError::ReferrerAccountKeyMismatch.into());
+    }
+    if referrer_account.owner != program_id {
+        return Err(AirdropPoolError::UserAccountOwnerMismatch.into());
+    }
+    check_writable(&[referrer_account])
+}
+
+// Accounts of a reward mint of the pool, [token_account] is the token account of the claimer or referrer, or the
+// destination token account of the authority
+pub struct RewardMintAccounts<'a, 'b> {
+    pub slot: usize,
+    pub reward_mint: RewardMint,
+    pub token_mint: &'a AccountInfo<'b>,
+    pub pool_token_account: &'a AccountInfo<'b>,
+    pub token_account: &'a AccountInfo<'b>,
+}
+
+// [accounts] are (reward mint, pool reward token account, token account of [wallet]) triples, one for
+// each reward mint of the pool in slot order
+fn parse_reward_mint_accounts<'a, 'b>(program: &AccountInfo,
+                                      token_program: &AccountInfo,
+                                      pool_account: &AccountInfo,
+                                      pool_account_state: &AirdropPool,
+                                      wallet: &Pubkey,
+                                      accounts: &'a [AccountInfo<'b>],
+                                      token_account_key_error: AirdropPoolError) -> Result<Vec<RewardMintAccounts<'a, 'b>>, ProgramError> {
+    let mut reward_mint_accounts = vec![];
+    for ((slot, reward_mint), reward_mint_account) in pool_account_state.get_reward_mints().into_iter().zip(accounts.chunks(3)) {
+        let token_mint = &reward_mint_account[0];
+        let pool_token_account = &reward_mint_account[1];
+        let token_account = &reward_mint_account[2];
+        if token_mint.key != &reward_mint.token_mint_id {
+            return Err(AirdropPoolError::RewardMintKeyMismatch.into());
+        }
+        if pool_token_account.key != &config::get_pool_reward_token_account(program.key, pool_account.key, token_mint.key).0 {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        if token_account.key != &config::get_claimer_token_account(token_program.key, token_mint.key, wallet) {
+            return Err(token_account_key_error.clone().into());
+        }
+        check_token_mint(token_program, token_mint)?;
+        check_writable(&[pool_token_account, token_account])?;
+        reward_mint_accounts.push(RewardMintAccounts {
+            slot,
+            reward_mint,
+            token_mint,
+            pool_token_account,
+            token_account,
+        });
+    }
+    Ok(reward_mint_accounts)
+}
+
+// [accounts] are (reward mint, pool reward token account, destination token account) triples, for reward mints of
+// the pool in slot order, each at most once
+fn parse_reward_vault_accounts<'a, 'b>(program: &AccountInfo,
+                                       token_program: &AccountInfo,
+                                       pool_account: &AccountInfo,
+                                       pool_account_state: &AirdropPool,
+                                       accounts: &'a [AccountInfo<'b>]) -> Result<Vec<RewardMintAccounts<'a, 'b>>, ProgramError> {
+    if !accounts.len().is_multiple_of(3) {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let mut reward_mint_accounts: Vec<RewardMintAccounts> = vec![];
+    for reward_mint_account in accounts.chunks(3) {
+        let token_mint = &reward_mint_account[0];
+        let pool_token_account = &reward_mint_account[1];
+        let token_account = &reward_mint_account[2];
+        let previous_slot = reward_mint_accounts.last().map(|previous_reward_mint_account| previous_reward_mint_account.slot);
+        let (slot, reward_mint) = pool_account_state.get_reward_mints().into_iter()
+            .find(|(slot, reward_mint)| reward_mint.token_mint_id == *token_mint.key && previous_slot.is_none_or(|previous_slot| *slot > previous_slot))
+            .ok_or(AirdropPoolError::RewardMintKeyMismatch)?;
+        if pool_token_account.key != &config::get_pool_reward_token_account(program.key, pool_account.key, token_mint.key).0 {
+            return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+        }
+        check_token_mint(token_program, token_mint)?;
+        check_token_account(token_program,
+                            token_mint,
+                            token_account,
+                            None,
+                            AirdropPoolError::DestinationTokenAccountMintMismatch,
+                            AirdropPoolError::DestinationTokenAccountMintMismatch)?;
+        check_writable(&[pool_token_account, token_account])?;
+        reward_mint_accounts.push(RewardMintAccounts {
+            slot,
+            reward_mint,
+            token_mint,
+            pool_token_account,
+            token_account,
+        });
+    }
+    Ok(reward_mint_accounts)
+}
+
+// Checks
+
+fn check_program(program: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    if program.key != program_id {
+        return Err(AirdropPoolError::ProgramKeyMismatch.into());
+    }
+    Ok(())
+}
+
+fn check_rent_sysvar(rent_sysvar: &AccountInfo) -> ProgramResult {
+    if rent_sysvar.key != &solana_program::sysvar::rent::id() {
+        return Err(AirdropPoolError::RentSysvarKeyMismatch.into());
+    }
+    Ok(())
+}
+
+fn check_clock_sysvar(clock_sysvar: &AccountInfo) -> ProgramResult {
+    if clock_sysvar.key != &solana_program::sysvar::clock::id() {
+        return Err(AirdropPoolError::ClockSysvarKeyMismatch.into());
+    }
+    Ok(())
+}
+
+fn check_system_program(system_program: &AccountInfo) -> ProgramResult {
+    if system_program.key != &solana_program::system_program::id() {
+        return Err(AirdropPoolError::SystemProgramKeyMismatch.into());
+    }
+    Ok(())
+}
+
+fn check_pool_account(pool_account: &AccountInfo, pool_account_id: &Pubkey, program_id: &Pubkey) -> ProgramResult {
+    if pool_account.key != pool_account_id {
+        return Err(AirdropPoolError::PoolAccountKeyMismatch.into());
+    }
+    if pool_account.owner != program_id {
+        return Err(AirdropPoolError::PoolAccountOwnerMismatch.into());
+    }
+    // Pools with a previous layout must be upgraded with MigratePool before being updated
+    if !AirdropPool::is_current_layout(*pool_account.data.borrow()) {
+        return Err(AirdropPoolError::AccountNotMigrated.into());
+    }
+    Ok(())
+}
+
+// Pools use the token program they were initialized with
+fn check_token_program(token_program: &AccountInfo, pool_account_state: &AirdropPool) -> ProgramResult {
+    if token_program.key != &pool_account_state.token_program_id {
+        return Err(AirdropPoolError::TokenProgramKeyMismatch.into());
+    }
+    Ok(())
+}
+
+fn check_token_mint(token_program: &AccountInfo, token_mint: &AccountInfo) -> ProgramResult {
+    // Native SOL pools use the native mint, which is owned by SPL Token
+    if token::is_native(token_program.key) {
+        if token_mint.key != &spl_token::native_mint::id() {
+            return Err(AirdropPoolError::NativeMintKeyMismatch.into());
+        }
+        return Ok(());
+    }
+    if token_mint.owner != token_program.key {
+        return Err(AirdropPoolError::TokenMintOwnerMismatch.into());
+    }
+    Ok(())
+}
+
+// [token_wallet] is the expected owner of the token account, if any
+fn check_token_account(token_program: &AccountInfo,
+                       token_mint: &AccountInfo,
+                       token_account: &AccountInfo,
+                       token_wallet: Option<&Pubkey>,
+                       mint_error: AirdropPoolError,
+                       owner_error: AirdropPoolError) -> ProgramResult {
+    // The token account of a wallet in a native SOL pool is the wallet itself
+    if token::is_native(token_program.key) {
+        if let Some(token_wallet) = token_wallet {
+            if token_account.key != token_wallet {
+                return Err(owner_error.into());
+            }
+        }
+        return Ok(());
+    }
+    if token_account.owner != token_program.key {
+        return Err(owner_error.into());
+    }
+    let token_account_state = token::unpack_account(token_account)?;
+    if &token_account_state.mint != token_mint.key {
+        return Err(mint_error.into());
+    }
+    if let Some(token_wallet) = token_wallet {
+        if &token_account_state.owner != token_wallet {
+            return Err(owner_error.into());
+        }
+    }
+    Ok(())
+}
+
+fn check_authority(authority: &AccountInfo, pool_account_state: &AirdropPool) -> ProgramResult {
+    if authority.key != &pool_account_state.authority {
+        return Err(AirdropPoolError::AuthorityKeyMismatch.into());
+    }
+    if !authority.is_signer {
+        return Err(AirdropPoolError::AuthorityDidNotSign.into());
+    }
+    Ok(())
+}
+
+// The ProgramData account holds the bincode encoding of UpgradeableLoaderState::ProgramData, a u32 variant
+// index of 3, the deployment slot and the optional upgrade authority. Immutable programs have no upgrade authority.
+fn check_upgrade_authority(program: &AccountInfo,
+                           program_data: &AccountInfo,
+                           upgrade_authority: &AccountInfo) -> ProgramResult {
+    if program_data.key != &config::get_program_data_account(program.key) {
+        return Err(AirdropPoolError::ProgramDataKeyMismatch.into());
+    }
+    if program_data.owner != &bpf_loader_upgradeable::id() {
+        return Err(AirdropPoolError::ProgramDataKeyMismatch.into());
+    }
+    let program_data_data = program_data.data.borrow();
+    let upgrade_authority_id = match program_data_data.get(..45) {
+        Some(header) if header[..4] == [3, 0, 0, 0] && header[12] == 1 => Pubkey::new(&header[13..45]),
+        _ => return Err(AirdropPoolError::UpgradeAuthorityMismatch.into()),
+    };
+    if upgrade_authority.key != &upgrade_authority_id {
+        return Err(AirdropPoolError::UpgradeAuthorityMismatch.into());
+    }
+    if !upgrade_authority.is_signer {
+        return Err(AirdropPoolError::AuthorityDidNotSign.into());
+    }
+    Ok(())
+}
+
+fn check_writable(accounts: &[&AccountInfo]) -> ProgramResult {
+    if accounts.iter().any(|account| !account.is_writable) {
+        return Err(AirdropPoolError::AccountNotWritable.into());
+    }
+    Ok(())
+}
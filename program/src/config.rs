@@ -1,4 +1,6 @@
+use solana_program::bpf_loader_upgradeable;
 use solana_program::pubkey::Pubkey;
+use solana_program::system_program;
 use spl_associated_token_account;
 
 #[inline(always)]
@@ -45,6 +47,30 @@ macro_rules! pool_token_account_seeds {
     };
 }
 
+// Pool token account of a reward mint of the pool
+#[inline(always)]
+pub fn get_pool_reward_token_account(program: &Pubkey, pool_account: &Pubkey, reward_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[
+        &program.to_bytes(),
+        &pool_account.to_bytes(),
+        &reward_mint.to_bytes(),
+        "pool_reward_token_account".as_bytes(),
+    ], program)
+}
+
+#[macro_export]
+macro_rules! pool_reward_token_account_seeds {
+    ($program:expr, $pool_account:expr, $reward_mint:expr, $bump_seed:expr) => {
+        &[
+            $program.as_ref(),
+            $pool_account.as_ref(),
+            $reward_mint.as_ref(),
+            "pool_reward_token_account".as_bytes(),
+            &[$bump_seed],
+        ]
+    };
+}
+
 #[inline(always)]
 pub fn get_claimer_account(program: &Pubkey,
                            pool_account: &Pubkey,
@@ -71,6 +97,41 @@ macro_rules! claimer_account_seeds {
 }
 
 #[inline(always)]
-pub fn get_claimer_token_account(token_mint: &Pubkey, user_wallet: &Pubkey) -> Pubkey {
-    return spl_associated_token_account::get_associated_token_address(user_wallet, token_mint);
+pub fn get_depositor_account(program: &Pubkey,
+                             pool_account: &Pubkey,
+                             depositor_wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[
+        &program.to_bytes(),
+        &pool_account.to_bytes(),
+        &depositor_wallet.to_bytes(),
+        "depositor_account".as_bytes(),
+    ], program)
+}
+
+#[macro_export]
+macro_rules! depositor_account_seeds {
+    ($program:expr, $pool_account:expr, $depositor_wallet: expr, $bump_seed:expr) => {
+        &[
+            $program.as_ref(),
+            $pool_account.as_ref(),
+            $depositor_wallet.as_ref(),
+            "depositor_account".as_bytes(),
+            &[$bump_seed],
+        ]
+    };
+}
+
+// ProgramData account of the upgradeable loader, which holds the upgrade authority of the program
+#[inline(always)]
+pub fn get_program_data_account(program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[&program.to_bytes()], &bpf_loader_upgradeable::id()).0
+}
+
+// Native SOL pools, initialized with the system program, pay the wallet itself
+#[inline(always)]
+pub fn get_claimer_token_account(token_program: &Pubkey, token_mint: &Pubkey, user_wallet: &Pubkey) -> Pubkey {
+    if token_program == &system_program::id() {
+        return *user_wallet;
+    }
+    return spl_associated_token_account::get_associated_token_address_with_program_id(user_wallet, token_mint, token_program);
 }
\ No newline at end of file
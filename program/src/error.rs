@@ -58,6 +58,148 @@ pub enum AirdropPoolError {
     FailedToPackData,
     #[error("FailedToUnpackData")]
     FailedToUnpackData,
+
+    #[error("AuthorityKeyMismatch")]
+    AuthorityKeyMismatch,
+    #[error("AuthorityDidNotSign")]
+    AuthorityDidNotSign,
+
+    #[error("TransferToDestinationFailed")]
+    TransferToDestinationFailed,
+    #[error("ClosePoolTokenAccountFailed")]
+    ClosePoolTokenAccountFailed,
+
+    #[error("ClockSysvarKeyMismatch")]
+    ClockSysvarKeyMismatch,
+    #[error("InvalidClaimWindow")]
+    InvalidClaimWindow,
+    #[error("ClaimNotStarted")]
+    ClaimNotStarted,
+    #[error("ClaimEnded")]
+    ClaimEnded,
+
+    #[error("PoolPaused")]
+    PoolPaused,
+
+    #[error("MerkleRootNotSet")]
+    MerkleRootNotSet,
+    #[error("MerkleProofRequired")]
+    MerkleProofRequired,
+    #[error("InvalidMerkleProof")]
+    InvalidMerkleProof,
+
+    #[error("InstructionsSysvarKeyMismatch")]
+    InstructionsSysvarKeyMismatch,
+    #[error("VoucherRequired")]
+    VoucherRequired,
+    #[error("InvalidVoucher")]
+    InvalidVoucher,
+    #[error("VoucherExpired")]
+    VoucherExpired,
+
+    #[error("InvalidVestingSchedule")]
+    InvalidVestingSchedule,
+    #[error("VestingNotEnabled")]
+    VestingNotEnabled,
+    #[error("NothingToRelease")]
+    NothingToRelease,
+
+    #[error("InvalidReferralRewards")]
+    InvalidReferralRewards,
+
+    #[error("NoPendingReferralRewards")]
+    NoPendingReferralRewards,
+
+    #[error("AssociatedTokenProgramKeyMismatch")]
+    AssociatedTokenProgramKeyMismatch,
+    #[error("InitClaimerTokenAccountFailed")]
+    InitClaimerTokenAccountFailed,
+
+    #[error("DepositorAccountKeyMismatch")]
+    DepositorAccountKeyMismatch,
+    #[error("DepositorDidNotSign")]
+    DepositorDidNotSign,
+    #[error("InvalidDepositAmount")]
+    InvalidDepositAmount,
+    #[error("InitDepositorAccountFailed")]
+    InitDepositorAccountFailed,
+    #[error("TransferToPoolFailed")]
+    TransferToPoolFailed,
+
+    #[error("PoolAlreadyMigrated")]
+    PoolAlreadyMigrated,
+    #[error("MigratePoolFailed")]
+    MigratePoolFailed,
+
+    #[error("UserAccountOwnerMismatch")]
+    UserAccountOwnerMismatch,
+    #[error("ClaimerDidNotSign")]
+    ClaimerDidNotSign,
+    #[error("PoolStillActive")]
+    PoolStillActive,
+    #[error("ClaimerHasPendingRewards")]
+    ClaimerHasPendingRewards,
+
+    #[error("TokenMintOwnerMismatch")]
+    TokenMintOwnerMismatch,
+    #[error("AccountNotWritable")]
+    AccountNotWritable,
+    #[error("FunderDidNotSign")]
+    FunderDidNotSign,
+    #[error("UserTokenAccountMintMismatch")]
+    UserTokenAccountMintMismatch,
+    #[error("UserTokenAccountOwnerMismatch")]
+    UserTokenAccountOwnerMismatch,
+    #[error("ReferrerTokenAccountMintMismatch")]
+    ReferrerTokenAccountMintMismatch,
+    #[error("ReferrerTokenAccountOwnerMismatch")]
+    ReferrerTokenAccountOwnerMismatch,
+    #[error("DepositorTokenAccountMintMismatch")]
+    DepositorTokenAccountMintMismatch,
+    #[error("DestinationTokenAccountMintMismatch")]
+    DestinationTokenAccountMintMismatch,
+    #[error("InvalidInstruction")]
+    InvalidInstruction,
+    #[error("ClaimerAlreadyMigrated")]
+    ClaimerAlreadyMigrated,
+    #[error("AccountNotMigrated")]
+    AccountNotMigrated,
+    #[error("GateRequirementNotMet")]
+    GateRequirementNotMet,
+    #[error("InvalidClaimFee")]
+    InvalidClaimFee,
+    #[error("FeeRecipientKeyMismatch")]
+    FeeRecipientKeyMismatch,
+    #[error("TransferClaimFeeFailed")]
+    TransferClaimFeeFailed,
+    #[error("SelfReferral")]
+    SelfReferral,
+    #[error("ReferralCycle")]
+    ReferralCycle,
+    #[error("DistributeAmountsMismatch")]
+    DistributeAmountsMismatch,
+    #[error("TooManyRewardMints")]
+    TooManyRewardMints,
+    #[error("RewardMintAlreadyAdded")]
+    RewardMintAlreadyAdded,
+    #[error("RewardMintsNotSupported")]
+    RewardMintsNotSupported,
+    #[error("RewardMintKeyMismatch")]
+    RewardMintKeyMismatch,
+    #[error("NativeMintKeyMismatch")]
+    NativeMintKeyMismatch,
+    #[error("PoolHasReservedRewards")]
+    PoolHasReservedRewards,
+    #[error("ProgramDataKeyMismatch")]
+    ProgramDataKeyMismatch,
+    #[error("UpgradeAuthorityMismatch")]
+    UpgradeAuthorityMismatch,
+    #[error("LegacyClaimNotSupported")]
+    LegacyClaimNotSupported,
+    #[error("RewardBelowRentExemption")]
+    RewardBelowRentExemption,
+    #[error("DepositorTokenAccountOwnerMismatch")]
+    DepositorTokenAccountOwnerMismatch,
 }
 
 impl From<AirdropPoolError> for ProgramError {
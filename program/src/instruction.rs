@@ -1,21 +1,139 @@
 use borsh::BorshDeserialize;
 use borsh::BorshSchema;
 use borsh::BorshSerialize;
+use solana_program::clock::UnixTimestamp;
 use solana_program::instruction::AccountMeta;
 use solana_program::instruction::Instruction;
 use solana_program::pubkey::Pubkey;
 
 use crate::config;
-use crate::packable::Packable;
+use crate::error::AirdropPoolError;
+
+// Instructions are encoded as [INSTRUCTION_VERSION] followed by the borsh encoding of the
+// instruction, without padding. Version bytes start at 0x80 so that they never collide with the
+// variant index of a legacy instruction.
+pub const INSTRUCTION_VERSION: u8 = 0x81;
+
+// Legacy instructions are the borsh encoding of Initialize or Claim, padded with zeros
+pub const LEGACY_PACKED_SIZE: usize = 34;
+
+// Parameters of a new pool
+#[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct InitializeArgs {
+    pub pool_account_nonce: [u8; 4],
+    pub reward_per_account: u64,
+    pub reward_per_referral: u64,
+    pub max_referral_depth: u8,
+    pub claim_start_unix_timestamp: Option<UnixTimestamp>,
+    pub claim_end_unix_timestamp: Option<UnixTimestamp>,
+    pub merkle_root: Option<[u8; 32]>,
+    pub voucher_signer: Option<Pubkey>,
+    pub vesting_duration_seconds: Option<u64>,
+    pub vesting_cliff_seconds: Option<u64>,
+    pub referral_rewards: Vec<u64>,
+    pub gate_mint: Option<Pubkey>,
+    pub gate_min_amount: u64,
+    pub claim_fee_lamports: u64,
+    pub fee_recipient: Pubkey,
+}
+
+// Accounts of Claim and ClaimWithProof, the pool token account and the accounts of the claimer are derived
+#[derive(Clone, Debug)]
+pub struct ClaimAccountKeys {
+    pub payer: Pubkey,
+    pub program: Pubkey,
+    pub rent_sysvar: Pubkey,
+    pub clock_sysvar: Pubkey,
+    pub instructions_sysvar: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+    pub associated_token_program: Pubkey,
+    pub token_mint: Pubkey,
+    pub pool_account: Pubkey,
+    pub claimer_wallet: Pubkey,
+    // Required by token gated pools
+    pub gate_token_account: Option<Pubkey>,
+    // Required by pools charging a claim fee
+    pub fee_recipient: Option<Pubkey>,
+    // Required by pools with reward mints
+    pub reward_mint_list: Vec<Pubkey>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DepositAccountKeys {
+    pub depositor_wallet: Pubkey,
+    pub program: Pubkey,
+    pub rent_sysvar: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+    pub token_mint: Pubkey,
+    pub pool_account: Pubkey,
+    pub depositor_token_account: Pubkey,
+}
+
+#[derive(Clone, Debug)]
+pub struct DistributeAccountKeys {
+    pub authority: Pubkey,
+    pub program: Pubkey,
+    pub rent_sysvar: Pubkey,
+    pub clock_sysvar: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+    pub associated_token_program: Pubkey,
+    pub token_mint: Pubkey,
+    pub pool_account: Pubkey,
+}
+
+#[derive(Clone, Debug)]
+pub struct AddRewardMintAccountKeys {
+    pub authority: Pubkey,
+    pub program: Pubkey,
+    pub rent_sysvar: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+    pub reward_mint: Pubkey,
+    pub pool_account: Pubkey,
+}
+
+// Accounts of Withdraw, the pool reward token accounts of [reward_mint_list] are emptied to the associated token
+// accounts of the authority
+#[derive(Clone, Debug)]
+pub struct WithdrawAccountKeys {
+    pub authority: Pubkey,
+    pub program: Pubkey,
+    pub token_program: Pubkey,
+    pub token_mint: Pubkey,
+    pub pool_account: Pubkey,
+    pub destination_token_account: Pubkey,
+    pub reward_mint_list: Vec<Pubkey>,
+}
+
+// Accounts of ClosePool, the pool reward token accounts are emptied to the associated token accounts of the
+// destination wallet. Pools with reward mints require all of them
+#[derive(Clone, Debug)]
+pub struct ClosePoolAccountKeys {
+    pub authority: Pubkey,
+    pub program: Pubkey,
+    pub token_program: Pubkey,
+    pub token_mint: Pubkey,
+    pub pool_account: Pubkey,
+    pub destination_token_account: Pubkey,
+    pub destination_wallet: Pubkey,
+    pub reward_mint_list: Vec<Pubkey>,
+}
 
 // TODO Find why rust thinks it's dead code
 
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub enum AirdropPoolInstruction {
-    Initialize {
+    // Boxed, so that the parameters of a new pool don't size every instruction
+    Initialize(#[allow(dead_code)] Box<InitializeArgs>),
+    Claim {
         #[allow(dead_code)]
-        pool_account_nonce: [u8; 4],
+        referrer: Option<Pubkey>,
+    },
+    UpdatePool {
         #[allow(dead_code)]
         reward_per_account: u64,
         #[allow(dead_code)]
@@ -23,7 +141,39 @@ pub enum AirdropPoolInstruction {
         #[allow(dead_code)]
         max_referral_depth: u8,
     },
-    Claim {
+    Withdraw {
+        #[allow(dead_code)]
+        amount: u64,
+    },
+    ClosePool,
+    Pause,
+    Resume,
+    ClaimWithProof {
+        #[allow(dead_code)]
+        amount: u64,
+        #[allow(dead_code)]
+        proof: Vec<[u8; 32]>,
+    },
+    Release,
+    WithdrawReferralRewards,
+    Deposit {
+        #[allow(dead_code)]
+        amount: u64,
+    },
+    MigratePool,
+    CloseClaimer,
+    Distribute {
+        #[allow(dead_code)]
+        amounts: Vec<u64>,
+    },
+    AddRewardMint {
+        #[allow(dead_code)]
+        reward_per_account: u64,
+        #[allow(dead_code)]
+        reward_per_referral: u64,
+    },
+    // Claim of the legacy encoding, sent by deployed clients with the accounts of the first release
+    LegacyClaim {
         #[allow(dead_code)]
         referrer: Option<Pubkey>,
     },
@@ -37,20 +187,12 @@ impl AirdropPoolInstruction {
         system_program: Pubkey,
         token_program: Pubkey,
         token_mint: Pubkey,
-        pool_account_nonce: [u8; 4],
-        reward_per_account: u64,
-        reward_per_referral: u64,
-        max_referral_depth: u8,
+        args: InitializeArgs,
     ) -> Instruction {
-        let (pool_account, _) = config::get_pool_account(&program, &token_mint, &pool_account_nonce);
+        let (pool_account, _) = config::get_pool_account(&program, &token_mint, &args.pool_account_nonce);
         let (pool_token_account, _) = config::get_pool_token_account(&program, &pool_account);
 
-        let object = AirdropPoolInstruction::Initialize {
-            pool_account_nonce,
-            reward_per_account,
-            reward_per_referral,
-            max_referral_depth,
-        };
+        let object = AirdropPoolInstruction::Initialize(Box::new(args));
         let data: Vec<u8> = object.pack();
 
         let accounts = vec![
@@ -67,48 +209,471 @@ impl AirdropPoolInstruction {
         Instruction::new_with_bytes(program, &data, accounts)
     }
 
+    // The payer funds the rent of the claimer accounts, so that relayers can sponsor claims of wallets without SOL
     pub fn claim(
+        keys: &ClaimAccountKeys,
+        referrer_wallet_list: &[Pubkey],
+    ) -> Instruction {
+        let object = AirdropPoolInstruction::Claim {
+            referrer: referrer_wallet_list.first().copied(),
+        };
+        let data: Vec<u8> = object.pack();
+
+        let mut accounts = get_claim_account_metas(keys);
+        for referrer_wallet in referrer_wallet_list {
+            let (referrer_account, _) = config::get_claimer_account(&keys.program, &keys.pool_account, referrer_wallet);
+            accounts.push(AccountMeta::new(referrer_account, false));
+        }
+
+        Instruction::new_with_bytes(keys.program, &data, accounts)
+    }
+
+    pub fn claim_with_proof(
+        keys: &ClaimAccountKeys,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Instruction {
+        let object = AirdropPoolInstruction::ClaimWithProof { amount, proof };
+        let data: Vec<u8> = object.pack();
+
+        let accounts = get_claim_account_metas(keys);
+
+        Instruction::new_with_bytes(keys.program, &data, accounts)
+    }
+
+    pub fn release(
         program: Pubkey,
-        rent_sysvar: Pubkey,
-        system_program: Pubkey,
+        clock_sysvar: Pubkey,
         token_program: Pubkey,
         token_mint: Pubkey,
         pool_account: Pubkey,
         claimer_wallet: Pubkey,
-        referrer_wallet_list: &[Pubkey],
     ) -> Instruction {
         let (pool_token_account, _) = config::get_pool_token_account(&program, &pool_account);
         let (claimer_account, _) = config::get_claimer_account(&program, &pool_account, &claimer_wallet);
-        let claimer_token_account = config::get_claimer_token_account(&token_mint, &claimer_wallet);
+        let claimer_token_account = config::get_claimer_token_account(&token_program, &token_mint, &claimer_wallet);
 
-        let object = AirdropPoolInstruction::Claim {
-            referrer: referrer_wallet_list.first().cloned(),
-        };
+        let object = AirdropPoolInstruction::Release;
+        let data: Vec<u8> = object.pack();
+
+        let accounts = vec![
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(clock_sysvar, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new(pool_account, false),
+            AccountMeta::new(pool_token_account, false),
+            AccountMeta::new_readonly(claimer_wallet, false),
+            AccountMeta::new(claimer_account, false),
+            AccountMeta::new(claimer_token_account, false),
+        ];
+
+        Instruction::new_with_bytes(program, &data, accounts)
+    }
+
+    pub fn withdraw_referral_rewards(
+        program: Pubkey,
+        token_program: Pubkey,
+        token_mint: Pubkey,
+        pool_account: Pubkey,
+        referrer_wallet: Pubkey,
+        reward_mint_list: &[Pubkey],
+    ) -> Instruction {
+        let (pool_token_account, _) = config::get_pool_token_account(&program, &pool_account);
+        let (referrer_account, _) = config::get_claimer_account(&program, &pool_account, &referrer_wallet);
+        let referrer_token_account = config::get_claimer_token_account(&token_program, &token_mint, &referrer_wallet);
+
+        let object = AirdropPoolInstruction::WithdrawReferralRewards;
         let data: Vec<u8> = object.pack();
 
         let mut accounts = vec![
             AccountMeta::new_readonly(program, false),
-            AccountMeta::new_readonly(rent_sysvar, false),
-            AccountMeta::new_readonly(system_program, false),
             AccountMeta::new_readonly(token_program, false),
             AccountMeta::new_readonly(token_mint, false),
             AccountMeta::new(pool_account, false),
             AccountMeta::new(pool_token_account, false),
+            AccountMeta::new_readonly(referrer_wallet, false),
+            AccountMeta::new(referrer_account, false),
+            AccountMeta::new(referrer_token_account, false),
+        ];
+
+        // Required by pools with reward mints
+        push_reward_mint_accounts(&mut accounts, &program, &token_program, &pool_account, &referrer_wallet, reward_mint_list, false);
+
+        Instruction::new_with_bytes(program, &data, accounts)
+    }
+
+    pub fn deposit(
+        keys: &DepositAccountKeys,
+        amount: u64,
+    ) -> Instruction {
+        let (pool_token_account, _) = config::get_pool_token_account(&keys.program, &keys.pool_account);
+        let (depositor_account, _) = config::get_depositor_account(&keys.program, &keys.pool_account, &keys.depositor_wallet);
+
+        let object = AirdropPoolInstruction::Deposit { amount };
+        let data: Vec<u8> = object.pack();
+
+        let accounts = vec![
+            AccountMeta::new(keys.depositor_wallet, true),
+            AccountMeta::new_readonly(keys.program, false),
+            AccountMeta::new_readonly(keys.rent_sysvar, false),
+            AccountMeta::new_readonly(keys.system_program, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+            AccountMeta::new_readonly(keys.token_mint, false),
+            AccountMeta::new(keys.pool_account, false),
+            AccountMeta::new(pool_token_account, false),
+            AccountMeta::new(keys.depositor_token_account, false),
+            AccountMeta::new(depositor_account, false),
+        ];
+
+        Instruction::new_with_bytes(keys.program, &data, accounts)
+    }
+
+    // [upgrade_authority] is required by pools created before the authority, it signs and becomes their authority
+    pub fn migrate_pool(
+        payer: Pubkey,
+        program: Pubkey,
+        system_program: Pubkey,
+        pool_account: Pubkey,
+        upgrade_authority: Option<Pubkey>,
+        claimer_wallet_list: &[Pubkey],
+    ) -> Instruction {
+        let object = AirdropPoolInstruction::MigratePool;
+        let data: Vec<u8> = object.pack();
+
+        let mut accounts = vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new(pool_account, false),
+        ];
+        if let Some(upgrade_authority) = upgrade_authority {
+            accounts.push(AccountMeta::new_readonly(config::get_program_data_account(&program), false));
+            accounts.push(AccountMeta::new_readonly(upgrade_authority, true));
+        }
+        for claimer_wallet in claimer_wallet_list {
+            let (claimer_account, _) = config::get_claimer_account(&program, &pool_account, claimer_wallet);
+            accounts.push(AccountMeta::new_readonly(*claimer_wallet, false));
+            accounts.push(AccountMeta::new(claimer_account, false));
+        }
+
+        Instruction::new_with_bytes(program, &data, accounts)
+    }
+
+    pub fn close_claimer(
+        program: Pubkey,
+        clock_sysvar: Pubkey,
+        pool_account: Pubkey,
+        claimer_wallet: Pubkey,
+    ) -> Instruction {
+        let (claimer_account, _) = config::get_claimer_account(&program, &pool_account, &claimer_wallet);
+
+        let object = AirdropPoolInstruction::CloseClaimer;
+        let data: Vec<u8> = object.pack();
+
+        let accounts = vec![
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(clock_sysvar, false),
+            AccountMeta::new_readonly(pool_account, false),
             AccountMeta::new(claimer_wallet, true),
             AccountMeta::new(claimer_account, false),
-            AccountMeta::new(claimer_token_account, false),
         ];
 
-        for referrer_wallet in referrer_wallet_list {
-            let (referrer_account, _) = config::get_claimer_account(&program, &pool_account, &referrer_wallet);
-            let referrer_token_account = config::get_claimer_token_account(&token_mint, &referrer_wallet);
-            accounts.push(AccountMeta::new(referrer_wallet.clone(), false));
-            accounts.push(AccountMeta::new(referrer_account, false));
-            accounts.push(AccountMeta::new(referrer_token_account, false));
+        Instruction::new_with_bytes(program, &data, accounts)
+    }
+
+    pub fn update_pool(
+        authority: Pubkey,
+        program: Pubkey,
+        pool_account: Pubkey,
+        reward_per_account: u64,
+        reward_per_referral: u64,
+        max_referral_depth: u8,
+    ) -> Instruction {
+        let object = AirdropPoolInstruction::UpdatePool {
+            reward_per_account,
+            reward_per_referral,
+            max_referral_depth,
+        };
+        let data: Vec<u8> = object.pack();
+
+        let accounts = vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new(pool_account, false),
+        ];
+
+        Instruction::new_with_bytes(program, &data, accounts)
+    }
+
+    pub fn withdraw(
+        keys: &WithdrawAccountKeys,
+        amount: u64,
+    ) -> Instruction {
+        let (pool_token_account, _) = config::get_pool_token_account(&keys.program, &keys.pool_account);
+
+        let object = AirdropPoolInstruction::Withdraw { amount };
+        let data: Vec<u8> = object.pack();
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(keys.authority, true),
+            AccountMeta::new_readonly(keys.program, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+            AccountMeta::new_readonly(keys.token_mint, false),
+            // Native SOL pools pay from the pool account itself
+            AccountMeta::new(keys.pool_account, false),
+            AccountMeta::new(pool_token_account, false),
+            AccountMeta::new(keys.destination_token_account, false),
+        ];
+        push_reward_mint_accounts(&mut accounts, &keys.program, &keys.token_program, &keys.pool_account, &keys.authority, &keys.reward_mint_list, false);
+
+        Instruction::new_with_bytes(keys.program, &data, accounts)
+    }
+
+    // Recipients are (wallet, amount) pairs, each recipient is marked as claimed
+    pub fn distribute(
+        keys: &DistributeAccountKeys,
+        recipient_list: &[(Pubkey, u64)],
+    ) -> Instruction {
+        let (pool_token_account, _) = config::get_pool_token_account(&keys.program, &keys.pool_account);
+
+        let object = AirdropPoolInstruction::Distribute {
+            amounts: recipient_list.iter().map(|(_, amount)| *amount).collect(),
+        };
+        let data: Vec<u8> = object.pack();
+
+        let mut accounts = vec![
+            AccountMeta::new(keys.authority, true),
+            AccountMeta::new_readonly(keys.program, false),
+            AccountMeta::new_readonly(keys.rent_sysvar, false),
+            AccountMeta::new_readonly(keys.clock_sysvar, false),
+            AccountMeta::new_readonly(keys.system_program, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+            AccountMeta::new_readonly(keys.associated_token_program, false),
+            AccountMeta::new_readonly(keys.token_mint, false),
+            AccountMeta::new(keys.pool_account, false),
+            AccountMeta::new(pool_token_account, false),
+        ];
+
+        for (recipient_wallet, _) in recipient_list {
+            let (recipient_account, _) = config::get_claimer_account(&keys.program, &keys.pool_account, recipient_wallet);
+            let recipient_token_account = config::get_claimer_token_account(&keys.token_program, &keys.token_mint, recipient_wallet);
+            accounts.push(AccountMeta::new_readonly(*recipient_wallet, false));
+            accounts.push(AccountMeta::new(recipient_account, false));
+            accounts.push(AccountMeta::new(recipient_token_account, false));
         }
 
+        Instruction::new_with_bytes(keys.program, &data, accounts)
+    }
+
+    pub fn add_reward_mint(
+        keys: &AddRewardMintAccountKeys,
+        reward_per_account: u64,
+        reward_per_referral: u64,
+    ) -> Instruction {
+        let (pool_reward_token_account, _) = config::get_pool_reward_token_account(&keys.program, &keys.pool_account, &keys.reward_mint);
+
+        let object = AirdropPoolInstruction::AddRewardMint {
+            reward_per_account,
+            reward_per_referral,
+        };
+        let data: Vec<u8> = object.pack();
+
+        let accounts = vec![
+            AccountMeta::new(keys.authority, true),
+            AccountMeta::new_readonly(keys.program, false),
+            AccountMeta::new_readonly(keys.rent_sysvar, false),
+            AccountMeta::new_readonly(keys.system_program, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+            AccountMeta::new_readonly(keys.reward_mint, false),
+            AccountMeta::new(keys.pool_account, false),
+            AccountMeta::new(pool_reward_token_account, false),
+        ];
+
+        Instruction::new_with_bytes(keys.program, &data, accounts)
+    }
+
+    pub fn close_pool(
+        keys: &ClosePoolAccountKeys,
+    ) -> Instruction {
+        let (pool_token_account, _) = config::get_pool_token_account(&keys.program, &keys.pool_account);
+
+        let object = AirdropPoolInstruction::ClosePool;
+        let data: Vec<u8> = object.pack();
+
+        // Transfer fees withheld in the pool token accounts are harvested to the mints before closing them
+        let mut accounts = vec![
+            AccountMeta::new_readonly(keys.authority, true),
+            AccountMeta::new_readonly(keys.program, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+            AccountMeta::new(keys.token_mint, false),
+            AccountMeta::new(keys.pool_account, false),
+            AccountMeta::new(pool_token_account, false),
+            AccountMeta::new(keys.destination_token_account, false),
+            AccountMeta::new(keys.destination_wallet, false),
+        ];
+        push_reward_mint_accounts(&mut accounts, &keys.program, &keys.token_program, &keys.pool_account, &keys.destination_wallet, &keys.reward_mint_list, true);
+
+        Instruction::new_with_bytes(keys.program, &data, accounts)
+    }
+
+    pub fn pause(
+        authority: Pubkey,
+        program: Pubkey,
+        pool_account: Pubkey,
+    ) -> Instruction {
+        let object = AirdropPoolInstruction::Pause;
+        let data: Vec<u8> = object.pack();
+
+        let accounts = vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new(pool_account, false),
+        ];
+
+        Instruction::new_with_bytes(program, &data, accounts)
+    }
+
+    pub fn resume(
+        authority: Pubkey,
+        program: Pubkey,
+        pool_account: Pubkey,
+    ) -> Instruction {
+        let object = AirdropPoolInstruction::Resume;
+        let data: Vec<u8> = object.pack();
+
+        let accounts = vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new(pool_account, false),
+        ];
+
         Instruction::new_with_bytes(program, &data, accounts)
     }
 }
 
-implement_packable!(AirdropPoolInstruction, 34);
+impl AirdropPoolInstruction {
+    pub fn pack(&self) -> Vec<u8> {
+        let mut result = vec![INSTRUCTION_VERSION];
+        result.extend(borsh::BorshSerialize::try_to_vec(self).unwrap());
+        result
+    }
+
+    pub fn unpack(data: &[u8]) -> Result<Self, AirdropPoolError> {
+        let (version, rest) = data.split_first()
+            .ok_or(AirdropPoolError::InvalidInstruction)?;
+        match *version {
+            INSTRUCTION_VERSION => {
+                AirdropPoolInstruction::try_from_slice(rest)
+                    .map_err(|_| AirdropPoolError::InvalidInstruction)
+            }
+            _ => Self::unpack_legacy(data),
+        }
+    }
+
+    // Deployed clients send the fixed size encoding, which has no version byte
+    fn unpack_legacy(mut data: &[u8]) -> Result<Self, AirdropPoolError> {
+        if data.len() != LEGACY_PACKED_SIZE {
+            return Err(AirdropPoolError::InvalidInstruction);
+        }
+        let legacy_instruction = LegacyAirdropPoolInstruction::deserialize(&mut data)
+            .map_err(|_| AirdropPoolError::InvalidInstruction)?;
+        Ok(legacy_instruction.into())
+    }
+}
+
+// Accounts shared by Claim and ClaimWithProof, without the referrer accounts
+fn get_claim_account_metas(keys: &ClaimAccountKeys) -> Vec<AccountMeta> {
+    let (pool_token_account, _) = config::get_pool_token_account(&keys.program, &keys.pool_account);
+    let (claimer_account, _) = config::get_claimer_account(&keys.program, &keys.pool_account, &keys.claimer_wallet);
+    let claimer_token_account = config::get_claimer_token_account(&keys.token_program, &keys.token_mint, &keys.claimer_wallet);
+
+    let mut accounts = vec![
+        AccountMeta::new(keys.payer, true),
+        AccountMeta::new_readonly(keys.program, false),
+        AccountMeta::new_readonly(keys.rent_sysvar, false),
+        AccountMeta::new_readonly(keys.clock_sysvar, false),
+        AccountMeta::new_readonly(keys.instructions_sysvar, false),
+        AccountMeta::new_readonly(keys.system_program, false),
+        AccountMeta::new_readonly(keys.token_program, false),
+        AccountMeta::new_readonly(keys.associated_token_program, false),
+        AccountMeta::new_readonly(keys.token_mint, false),
+        AccountMeta::new(keys.pool_account, false),
+        AccountMeta::new(pool_token_account, false),
+        AccountMeta::new(keys.claimer_wallet, true),
+        AccountMeta::new(claimer_account, false),
+        AccountMeta::new(claimer_token_account, false),
+    ];
+
+    // Required by token gated pools
+    if let Some(gate_token_account) = keys.gate_token_account {
+        accounts.push(AccountMeta::new_readonly(gate_token_account, false));
+    }
+    // Required by pools charging a claim fee
+    if let Some(fee_recipient) = keys.fee_recipient {
+        accounts.push(AccountMeta::new(fee_recipient, false));
+    }
+    // Required by pools with reward mints
+    push_reward_mint_accounts(&mut accounts, &keys.program, &keys.token_program, &keys.pool_account, &keys.claimer_wallet, &keys.reward_mint_list, false);
+
+    accounts
+}
+
+// Reward mint, pool reward token account and token account of [wallet] for each reward mint of the pool. The reward
+// mints are writable when their pool reward token accounts are closed, to harvest their withheld transfer fees
+fn push_reward_mint_accounts(accounts: &mut Vec<AccountMeta>,
+                             program: &Pubkey,
+                             token_program: &Pubkey,
+                             pool_account: &Pubkey,
+                             wallet: &Pubkey,
+                             reward_mint_list: &[Pubkey],
+                             is_reward_mint_writable: bool) {
+    for reward_mint in reward_mint_list {
+        let (pool_reward_token_account, _) = config::get_pool_reward_token_account(program, pool_account, reward_mint);
+        let reward_token_account = config::get_claimer_token_account(token_program, reward_mint, wallet);
+        if is_reward_mint_writable {
+            accounts.push(AccountMeta::new(*reward_mint, false));
+        } else {
+            accounts.push(AccountMeta::new_readonly(*reward_mint, false));
+        }
+        accounts.push(AccountMeta::new(pool_reward_token_account, false));
+        accounts.push(AccountMeta::new(reward_token_account, false));
+    }
+}
+
+#[derive(BorshDeserialize)]
+enum LegacyAirdropPoolInstruction {
+    Initialize {
+        pool_account_nonce: [u8; 4],
+        reward_per_account: u64,
+        reward_per_referral: u64,
+        max_referral_depth: u8,
+    },
+    Claim {
+        referrer: Option<Pubkey>,
+    },
+}
+
+impl From<LegacyAirdropPoolInstruction> for AirdropPoolInstruction {
+    fn from(legacy_instruction: LegacyAirdropPoolInstruction) -> Self {
+        match legacy_instruction {
+            LegacyAirdropPoolInstruction::Initialize {
+                pool_account_nonce,
+                reward_per_account,
+                reward_per_referral,
+                max_referral_depth,
+            } => {
+                AirdropPoolInstruction::Initialize(Box::new(InitializeArgs {
+                    pool_account_nonce,
+                    reward_per_account,
+                    reward_per_referral,
+                    max_referral_depth,
+                    ..InitializeArgs::default()
+                }))
+            }
+            LegacyAirdropPoolInstruction::Claim { referrer } => {
+                AirdropPoolInstruction::LegacyClaim { referrer }
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 extern crate spl_token;
+extern crate spl_token_2022;
 extern crate solana_program;
 extern crate num_derive;
 extern crate thiserror;
@@ -8,6 +9,11 @@ pub mod packable;
 #[macro_use]
 pub mod config;
 pub mod instruction;
+pub mod merkle;
+pub mod voucher;
+pub mod vesting;
+pub mod token;
+pub mod accounts;
 pub mod state;
 pub mod entrypoint;
 pub mod processor;
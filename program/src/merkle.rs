@@ -0,0 +1,57 @@
+//! Merkle tree of allowlist pools
+//!
+//! Each leaf commits to a (claimer wallet, amount) pair. Pairs of nodes are hashed in sorted
+//! order, so a proof is only the list of sibling hashes from the leaf to the root.
+
+use solana_program::hash::hashv;
+use solana_program::pubkey::Pubkey;
+
+pub fn get_leaf(claimer_wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[claimer_wallet.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[&a[..], &b[..]]).to_bytes()
+    } else {
+        hashv(&[&b[..], &a[..]]).to_bytes()
+    }
+}
+
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed_root = proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    &computed_root == root
+}
+
+// Off-chain helpers, a node without sibling is moved up to the next level as is.
+
+pub fn get_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut level = leaves.to_vec();
+    if level.is_empty() {
+        return [0; 32];
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| if pair.len() == 2 { hash_pair(&pair[0], &pair[1]) } else { pair[0] })
+            .collect();
+    }
+    level[0]
+}
+
+pub fn get_proof(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut result = vec![];
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let sibling_index = index ^ 1;
+        if sibling_index < level.len() {
+            result.push(level[sibling_index]);
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| if pair.len() == 2 { hash_pair(&pair[0], &pair[1]) } else { pair[0] })
+            .collect();
+        index /= 2;
+    }
+    result
+}
\ No newline at end of file
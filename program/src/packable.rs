@@ -38,4 +38,55 @@ macro_rules! implement_packable {
             }
         }
     };
-}
\ No newline at end of file
+}
+
+
+// Versioned accounts start with a discriminator byte, which identifies both the account type and the
+// version of its layout. [PACKED_SIZE] includes the discriminator and may leave a zeroed tail after the
+// fields. Accounts with a previous layout are recognized by their size, unpacked with their own type and converted.
+#[macro_export]
+macro_rules! implement_versioned_packable {
+    ($for_type:ty, $discriminator:expr, $packed_size:expr, [$($previous_type:ty),*]) => {
+        impl $for_type {
+            pub const DISCRIMINATOR: u8 = $discriminator;
+
+            // Whether [data] has the current layout, otherwise the account needs to be migrated
+            pub fn is_current_layout(data: &[u8]) -> bool {
+                data.len() == <Self as Packable>::PACKED_SIZE && data[0] == Self::DISCRIMINATOR
+            }
+        }
+
+        impl Packable for $for_type {
+            const PACKED_SIZE: usize = $packed_size;
+
+            fn unpack(data: &[u8]) -> Result<Self, crate::error::AirdropPoolError> {
+                if Self::is_current_layout(data) {
+                    let mut state_data = &data[1..];
+                    return borsh::BorshDeserialize::deserialize(&mut state_data)
+                        .map_err(|_| crate::error::AirdropPoolError::FailedToUnpackData);
+                }
+                $(
+                    if data.len() == <$previous_type as Packable>::PACKED_SIZE {
+                        return <$previous_type as Packable>::unpack(data).map(Into::into);
+                    }
+                )*
+                Err(crate::error::AirdropPoolError::FailedToUnpackData)
+            }
+
+            fn pack(&self) -> Vec<u8> {
+                let mut result = vec![Self::DISCRIMINATOR];
+                result.extend(borsh::BorshSerialize::try_to_vec(self).unwrap());
+                result.resize(Self::PACKED_SIZE, 0);
+                result
+            }
+
+            fn pack_into(&self, data: &mut [u8]) -> Result<(), crate::error::AirdropPoolError> {
+                if data.len() != Self::PACKED_SIZE {
+                    return Err(crate::error::AirdropPoolError::FailedToPackData);
+                }
+                data.copy_from_slice(&self.pack());
+                Ok(())
+            }
+        }
+    };
+}
@@ -1,45 +1,106 @@
 use solana_program;
 use solana_program::account_info::AccountInfo;
 use solana_program::account_info::next_account_info;
+use solana_program::clock::Clock;
 use solana_program::entrypoint::ProgramResult;
+use solana_program::program::invoke;
 use solana_program::program::invoke_signed;
-use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::system_instruction;
 use solana_program::sysvar::Sysvar;
-use spl_token;
-use spl_token::state::Account as SplTokenAccount;
+use spl_associated_token_account;
+use spl_token_2022;
 
-use crate::config;
+use crate::accounts::{
+    self,
+    AddRewardMintAccounts,
+    ClaimAccounts,
+    CloseClaimerAccounts,
+    ClosePoolAccounts,
+    DepositAccounts,
+    DistributeAccounts,
+    InitializeAccounts,
+    MigratePoolAccounts,
+    ReleaseAccounts,
+    UpdatePoolAccounts,
+    WithdrawAccounts,
+    WithdrawReferralRewardsAccounts,
+};
 use crate::error::AirdropPoolError;
-use crate::instruction::AirdropPoolInstruction;
+use crate::instruction::{AirdropPoolInstruction, InitializeArgs};
+use crate::merkle;
 use crate::packable::Packable;
-use crate::state::{AirdropClaimer, AirdropPool};
+use crate::state::{AirdropClaimer, AirdropDepositor, AirdropPool, MAX_REFERRAL_DEPTH, MAX_REWARD_MINTS, RewardMint};
+use crate::token;
+use crate::vesting;
+use crate::voucher;
 
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    assert_eq!(instruction_data.len(), AirdropPoolInstruction::PACKED_SIZE);
     let instruction: AirdropPoolInstruction = AirdropPoolInstruction::unpack(instruction_data)?;
     match instruction {
-        AirdropPoolInstruction::Initialize {
-            pool_account_nonce,
+        AirdropPoolInstruction::Initialize(args) => {
+            process_initialize(program_id, accounts, *args)
+        }
+        AirdropPoolInstruction::Claim { referrer } => {
+            process_claim(program_id, ClaimAccounts::parse(program_id, accounts)?, referrer, None)
+        }
+        AirdropPoolInstruction::UpdatePool {
             reward_per_account,
             reward_per_referral,
             max_referral_depth,
         } => {
-            process_initialize(program_id,
-                               accounts,
-                               pool_account_nonce,
-                               reward_per_account,
-                               reward_per_referral,
-                               max_referral_depth)
+            process_update_pool(program_id,
+                                accounts,
+                                reward_per_account,
+                                reward_per_referral,
+                                max_referral_depth)
         }
-        AirdropPoolInstruction::Claim { referrer } => {
-            process_claim(program_id, accounts, referrer)
+        AirdropPoolInstruction::Withdraw { amount } => {
+            process_withdraw(program_id, accounts, amount)
+        }
+        AirdropPoolInstruction::ClosePool => {
+            process_close_pool(program_id, accounts)
+        }
+        AirdropPoolInstruction::Pause => {
+            process_set_paused(program_id, accounts, 1)
+        }
+        AirdropPoolInstruction::Resume => {
+            process_set_paused(program_id, accounts, 0)
+        }
+        AirdropPoolInstruction::ClaimWithProof { amount, proof } => {
+            process_claim(program_id, ClaimAccounts::parse(program_id, accounts)?, None, Some((amount, proof)))
+        }
+        AirdropPoolInstruction::Release => {
+            process_release(program_id, accounts)
+        }
+        AirdropPoolInstruction::WithdrawReferralRewards => {
+            process_withdraw_referral_rewards(program_id, accounts)
+        }
+        AirdropPoolInstruction::Deposit { amount } => {
+            process_deposit(program_id, accounts, amount)
+        }
+        AirdropPoolInstruction::MigratePool => {
+            process_migrate_pool(program_id, accounts)
+        }
+        AirdropPoolInstruction::CloseClaimer => {
+            process_close_claimer(program_id, accounts)
+        }
+        AirdropPoolInstruction::Distribute { amounts } => {
+            process_distribute(program_id, accounts, amounts)
+        }
+        AirdropPoolInstruction::AddRewardMint {
+            reward_per_account,
+            reward_per_referral,
+        } => {
+            process_add_reward_mint(program_id, accounts, reward_per_account, reward_per_referral)
+        }
+        AirdropPoolInstruction::LegacyClaim { referrer } => {
+            process_claim(program_id, ClaimAccounts::parse_legacy(program_id, accounts)?, referrer, None)
         }
     }
 }
@@ -47,219 +108,323 @@ pub fn process_instruction(
 pub fn process_initialize(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    pool_account_nonce: [u8; 4],
-    reward_per_account: u64,
-    reward_per_referral: u64,
-    max_referral_depth: u8,
+    args: InitializeArgs,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-
-    let funder = next_account_info(accounts_iter)?;
-    let program = next_account_info(accounts_iter)?;
-    let rent_sysvar = next_account_info(accounts_iter)?;
-    let system_program = next_account_info(accounts_iter)?;
-    let token_program = next_account_info(accounts_iter)?;
-    let token_mint = next_account_info(accounts_iter)?;
-    let pool_account = next_account_info(accounts_iter)?;
-    let pool_token_account = next_account_info(accounts_iter)?;
-
-    // println!("process_initialize: funder={}, (owner={})", funder.key, funder.owner);
-    // println!("process_initialize: program={}, (owner={})", program.key, program.owner);
-    // println!("process_initialize: rent_sysvar={}, (owner={})", rent_sysvar.key, rent_sysvar.owner);
-    // println!("process_initialize: system_program={}, (owner={})", system_program.key, system_program.owner);
-    // println!("process_initialize: token_program={}, (owner={})", token_program.key, token_program.owner);
-    // println!("process_initialize: token_mint={}, (owner={})", token_mint.key, token_mint.owner);
-    // println!("process_initialize: pool_account={}, (owner={})", pool_account.key, pool_account.owner);
-    // println!("process_initialize: pool_token_account={}, (owner={})", pool_token_account.key, pool_token_account.owner);
-
-    //
+    let InitializeAccounts {
+        funder,
+        program,
+        rent_sysvar,
+        system_program,
+        token_program,
+        token_mint,
+        pool_account,
+        pool_token_account,
+        pool_account_bump_seed,
+        pool_token_account_bump_seed,
+    } = InitializeAccounts::parse(program_id, accounts, &args.pool_account_nonce)?;
 
-    let rent = Rent::from_account_info(rent_sysvar)?;
-
-    //
+    // Unpack states
 
-    let (pool_account_id, pool_account_bump_seed) = config::get_pool_account(program.key, token_mint.key, &pool_account_nonce);
-    let (pool_token_account_id, pool_token_account_bump_seed) = config::get_pool_token_account(program.key, pool_account.key);
+    let rent = Rent::from_account_info(rent_sysvar)?;
 
-    //
+    // Validate parameters
 
-    if program.key != program_id {
-        return Err(AirdropPoolError::ProgramKeyMismatch.into());
-    }
-    if rent_sysvar.key != &solana_program::sysvar::rent::id() {
-        return Err(AirdropPoolError::RentSysvarKeyMismatch.into());
+    if let (Some(claim_start), Some(claim_end)) = (args.claim_start_unix_timestamp, args.claim_end_unix_timestamp) {
+        if claim_start >= claim_end {
+            return Err(AirdropPoolError::InvalidClaimWindow.into());
+        }
     }
-    if system_program.key != &solana_program::system_program::id() {
-        return Err(AirdropPoolError::SystemProgramKeyMismatch.into());
+    match (args.vesting_duration_seconds, args.vesting_cliff_seconds) {
+        (Some(0), _) | (None, Some(_)) => return Err(AirdropPoolError::InvalidVestingSchedule.into()),
+        (Some(duration), Some(cliff)) if cliff > duration => return Err(AirdropPoolError::InvalidVestingSchedule.into()),
+        _ => {}
     }
-    if pool_account.key != &pool_account_id {
-        return Err(AirdropPoolError::PoolAccountKeyMismatch.into());
+    let referral_rewards = if args.referral_rewards.is_empty() {
+        None
+    } else {
+        if args.referral_rewards.len() != args.max_referral_depth as usize || args.referral_rewards.len() > MAX_REFERRAL_DEPTH {
+            return Err(AirdropPoolError::InvalidReferralRewards.into());
+        }
+        let mut result = [0; MAX_REFERRAL_DEPTH];
+        result[..args.referral_rewards.len()].copy_from_slice(&args.referral_rewards);
+        Some(result)
+    };
+    if args.claim_fee_lamports > 0 && args.fee_recipient == Pubkey::default() {
+        return Err(AirdropPoolError::InvalidClaimFee.into());
     }
-    if pool_token_account.key != &pool_token_account_id {
-        return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+    // Native SOL rewards paid to an empty wallet must make it rent exempt, merkle pools pay the proven amounts
+    if token::is_native(token_program.key) && args.merkle_root.is_none() && args.reward_per_account < rent.minimum_balance(0) {
+        return Err(AirdropPoolError::RewardBelowRentExemption.into());
     }
 
     // Initialize program account
 
+    let pool_account_state = AirdropPool {
+        token_program_id: *token_program.key,
+        token_mint_id: *token_mint.key,
+        account_nonce: args.pool_account_nonce,
+        reward_per_account: args.reward_per_account,
+        reward_per_referral: args.reward_per_referral,
+        max_referral_depth: args.max_referral_depth,
+        authority: *funder.key,
+        claim_start_unix_timestamp: args.claim_start_unix_timestamp,
+        claim_end_unix_timestamp: args.claim_end_unix_timestamp,
+        paused: 0,
+        merkle_root: args.merkle_root,
+        voucher_signer: args.voucher_signer,
+        vesting_duration_seconds: args.vesting_duration_seconds,
+        vesting_cliff_seconds: args.vesting_cliff_seconds,
+        referral_rewards,
+        total_deposited: 0,
+        total_claimers: 0,
+        total_base_rewards_paid: 0,
+        total_referral_rewards_paid: 0,
+        gate_mint: args.gate_mint,
+        gate_min_amount: args.gate_min_amount,
+        claim_fee_lamports: args.claim_fee_lamports,
+        fee_recipient: args.fee_recipient,
+        reward_mints: [None; MAX_REWARD_MINTS],
+        reserved_amount: 0,
+    };
     init_pool_account(funder,
                       program,
                       system_program,
-                      token_program,
-                      token_mint,
                       pool_account,
                       &rent,
-                      pool_account_nonce,
-                      reward_per_account,
-                      reward_per_referral,
-                      max_referral_depth,
+                      &pool_account_state,
                       pool_account_bump_seed)
         .map_err(|_| AirdropPoolError::InitPoolAccountFailed)?;
 
-    // Initialize program token account
-
-    init_pool_token_account(funder,
-                            program,
-                            rent_sysvar,
-                            system_program,
-                            token_program,
-                            token_mint,
-                            pool_account,
-                            pool_token_account,
-                            &rent,
-                            pool_token_account_bump_seed)
-        .map_err(|_| AirdropPoolError::InitPoolTokenAccountFailed)?;
+    // Initialize program token account, native SOL pools hold their lamports in the pool account
+
+    if !token::is_native(token_program.key) {
+        create_pool_owned_token_account(funder,
+                                        system_program,
+                                        token_program,
+                                        token_mint,
+                                        pool_token_account,
+                                        &rent,
+                                        pool_token_account_seeds!(program.key, pool_account.key, pool_token_account_bump_seed))
+            .and_then(|_| initialize_pool_owned_token_account(rent_sysvar, token_program, token_mint, pool_account, pool_token_account))
+            .map_err(|_| AirdropPoolError::InitPoolTokenAccountFailed)?;
+    }
 
     Ok(())
 }
 
+// [allowance] is the (amount, merkle proof) of a claimer of an allowlist pool
 pub fn process_claim(
     program_id: &Pubkey,
-    accounts: &[AccountInfo],
+    claim_accounts: ClaimAccounts,
     referrer: Option<Pubkey>,
+    allowance: Option<(u64, Vec<[u8; 32]>)>,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-
-    // Get accounts
-
-    let program = next_account_info(accounts_iter)?;
-    if program.key != program_id {
-        return Err(AirdropPoolError::ProgramKeyMismatch.into());
-    }
-
-    let rent_sysvar = next_account_info(accounts_iter)?;
-    let system_program = next_account_info(accounts_iter)?;
-    let token_program = next_account_info(accounts_iter)?;
-    let token_mint = next_account_info(accounts_iter)?;
-    let pool_account = next_account_info(accounts_iter)?;
-    let pool_token_account = next_account_info(accounts_iter)?;
-    let claimer_wallet = next_account_info(accounts_iter)?;
-    let claimer_account = next_account_info(accounts_iter)?;
-    let claimer_token_account = next_account_info(accounts_iter)?;
-
-    // println!("process_claim: program={}, (owner={})", program.key, program.owner);
-    // println!("process_claim: token_program={}, (owner={})", token_program.key, token_program.owner);
-    // println!("process_claim: token_mint={}, (owner={})", token_mint.key, token_mint.owner);
-    // println!("process_claim: pool_account={}, (owner={})", pool_account.key, pool_account.owner);
-    // println!("process_claim: pool_token_account={}, (owner={})", pool_token_account.key, pool_token_account.owner);
-    // println!("process_claim: user_wallet={}, (owner={})", claimer_wallet.key, claimer_wallet.owner);
-    // println!("process_claim: user_account={}, (owner={})", claimer_account.key, claimer_account.owner);
-    // println!("process_claim: user_token_account={}, (owner={})", claimer_token_account.key, claimer_token_account.owner);
-    // println!("process_claim: referrer={:?}", referrer);
+    let ClaimAccounts {
+        payer,
+        program,
+        rent_sysvar,
+        clock_sysvar,
+        instructions_sysvar,
+        system_program,
+        token_program,
+        associated_token_program,
+        token_mint,
+        pool_account,
+        pool_token_account,
+        claimer_wallet,
+        claimer_account,
+        claimer_token_account,
+        gate_token_account,
+        fee_recipient,
+        reward_mint_accounts,
+        referrer_accounts,
+        mut pool_account_state,
+        pool_account_bump_seed,
+        claimer_account_bump_seed,
+    } = claim_accounts;
 
     // Unpack states
 
     let rent = Rent::from_account_info(rent_sysvar)?;
-    let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
-    let pool_token_account_state = SplTokenAccount::unpack(*pool_token_account.data.borrow())?;
-
-    //
+    let clock = match clock_sysvar {
+        Some(clock_sysvar) => Clock::from_account_info(clock_sysvar)?,
+        None => Clock::get()?,
+    };
+    let pool_token_amount = token::get_pool_amount(token_program, pool_account, pool_token_account)?;
 
-    let (pool_account_id, pool_account_bump_seed) = config::get_pool_account(program.key, token_mint.key, &pool_account_state.account_nonce);
-    let (pool_token_account_id, _) = config::get_pool_token_account(program.key, pool_account.key);
-    let (claimer_account_id, claimer_account_bump_seed) = config::get_claimer_account(program.key, pool_account.key, claimer_wallet.key);
-
-    // Validate keys
+    // Validate state
 
-    if program.key != program_id {
-        return Err(AirdropPoolError::ProgramKeyMismatch.into());
+    if pool_account_state.paused != 0 {
+        return Err(AirdropPoolError::PoolPaused.into());
     }
-    if rent_sysvar.key != &solana_program::sysvar::rent::id() {
-        return Err(AirdropPoolError::RentSysvarKeyMismatch.into());
+    if let Some(claim_start) = pool_account_state.claim_start_unix_timestamp {
+        if clock.unix_timestamp < claim_start {
+            return Err(AirdropPoolError::ClaimNotStarted.into());
+        }
     }
-    if pool_account.key != &pool_account_id {
-        return Err(AirdropPoolError::PoolAccountKeyMismatch.into());
+    if let Some(claim_end) = pool_account_state.claim_end_unix_timestamp {
+        if clock.unix_timestamp >= claim_end {
+            return Err(AirdropPoolError::ClaimEnded.into());
+        }
     }
-    if pool_account.owner != program_id {
-        return Err(AirdropPoolError::PoolAccountOwnerMismatch.into());
+
+    let reward_per_account = match (pool_account_state.merkle_root, allowance) {
+        (None, None) => pool_account_state.reward_per_account,
+        (None, Some(_)) => return Err(AirdropPoolError::MerkleRootNotSet.into()),
+        (Some(_), None) => return Err(AirdropPoolError::MerkleProofRequired.into()),
+        (Some(merkle_root), Some((amount, proof))) => {
+            if !merkle::verify(&proof, &merkle_root, merkle::get_leaf(claimer_wallet.key, amount)) {
+                return Err(AirdropPoolError::InvalidMerkleProof.into());
+            }
+            amount
+        }
+    };
+
+    if let Some(voucher_signer) = pool_account_state.voucher_signer {
+        let instructions_sysvar = instructions_sysvar.ok_or(AirdropPoolError::LegacyClaimNotSupported)?;
+        voucher::verify(instructions_sysvar,
+                        &voucher_signer,
+                        pool_account.key,
+                        claimer_wallet.key,
+                        clock.slot)?;
     }
-    if pool_token_account.key != &pool_token_account_id {
-        return Err(AirdropPoolError::PoolTokenAccountKeyMismatch.into());
+
+    // Token gated pools, the gate token account must be a token account of the claimer holding enough of the gate mint
+    if let (Some(gate_mint), Some(gate_token_account)) = (pool_account_state.gate_mint, gate_token_account) {
+        let is_gate_requirement_met = token::is_token_program(gate_token_account.owner)
+            && match token::unpack_account(gate_token_account) {
+                Ok(gate_token_account_state) => {
+                    gate_token_account_state.mint == gate_mint
+                        && gate_token_account_state.owner == *claimer_wallet.key
+                        && gate_token_account_state.amount >= pool_account_state.gate_min_amount
+                }
+                Err(_) => false,
+            };
+        if !is_gate_requirement_met {
+            return Err(AirdropPoolError::GateRequirementNotMet.into());
+        }
     }
-    if claimer_account.key != &claimer_account_id {
-        return Err(AirdropPoolError::UserAccountKeyMismatch.into());
+
+    if pool_account_state.get_available_amount(pool_token_amount) < reward_per_account {
+        return Err(AirdropPoolError::InsufficientBalance.into());
     }
-    if claimer_token_account.key != &config::get_claimer_token_account(token_mint.key, claimer_wallet.key) {
-        return Err(AirdropPoolError::UserTokenAccountKeyMismatch.into());
+    if referrer == Some(*claimer_wallet.key) {
+        return Err(AirdropPoolError::SelfReferral.into());
     }
 
-    // Validate state
+    let is_vesting = pool_account_state.vesting_duration_seconds.is_some();
+    let transfer_fee = token::get_transfer_fee(token_program, token_mint)?;
+    let reward_mint_transfer_fees = reward_mint_accounts.iter()
+        .map(|reward_mint_account| token::get_transfer_fee(token_program, reward_mint_account.token_mint))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut referral_rewards_paid = 0;
 
-    if pool_token_account_state.amount < pool_account_state.reward_per_account {
-        return Err(AirdropPoolError::InsufficientBalance.into());
+    // Charge the claim fee
+
+    if let Some(fee_recipient) = fee_recipient {
+        invoke(
+            &system_instruction::transfer(claimer_wallet.key, fee_recipient.key, pool_account_state.claim_fee_lamports),
+            &[
+                claimer_wallet.clone(),
+                fee_recipient.clone(),
+                system_program.clone(),
+            ],
+        ).map_err(|_| AirdropPoolError::TransferClaimFeeFailed)?;
     }
 
-    // Reward referrers
+    // Reward referrers, they withdraw their rewards later so that their token accounts can't block claims
 
     {
+        let referrer_accounts_iter = &mut referrer_accounts.iter().copied();
         let mut depth = 1;
         let mut expected_referrer_wallet_id_option = referrer;
-        let mut referrer_wallet: &AccountInfo;
         let mut referrer_account: &AccountInfo;
-        let mut referrer_token_account: &AccountInfo;
         let mut referrer_account_state: AirdropClaimer;
+        // Wallets of the chain, a wallet is paid at most once per claim
+        let mut chain_wallet_ids = vec![*claimer_wallet.key];
 
         while let Some(expected_referrer_wallet_id) = expected_referrer_wallet_id_option {
             if depth > pool_account_state.max_referral_depth { break; }
-
-            referrer_wallet = next_account_info(accounts_iter)?;
-            referrer_account = next_account_info(accounts_iter)?;
-            referrer_token_account = next_account_info(accounts_iter)?;
-
-            if referrer_wallet.key != &expected_referrer_wallet_id {
-                return Err(AirdropPoolError::ReferrerWalletKeyMismatch.into());
-            }
-            if referrer_account.key != &config::get_claimer_account(program_id, pool_account.key, referrer_wallet.key).0 {
-                return Err(AirdropPoolError::ReferrerAccountKeyMismatch.into());
-            }
-            if referrer_token_account.key != &config::get_claimer_token_account(token_mint.key, referrer_wallet.key) {
-                return Err(AirdropPoolError::ReferrerTokenAccountKeyMismatch.into());
+            if chain_wallet_ids.contains(&expected_referrer_wallet_id) {
+                return Err(AirdropPoolError::ReferralCycle.into());
             }
+            chain_wallet_ids.push(expected_referrer_wallet_id);
+
+            referrer_account = next_account_info(referrer_accounts_iter)?;
+            accounts::check_referrer_account(program_id, pool_account, referrer_account, &expected_referrer_wallet_id)?;
 
             referrer_account_state = AirdropClaimer::unpack(&referrer_account.data.borrow())?;
+            // Referrers with a previous layout are migrated by the claim, the payer funds their rent exemption
+            if !AirdropClaimer::is_current_layout(*referrer_account.data.borrow()) {
+                migrate_account(payer, system_program, referrer_account, AirdropClaimer::PACKED_SIZE)?;
+            }
 
             if referrer_account_state.claimed == 0 {
                 return Err(AirdropPoolError::ReferrerDidNotClaim.into());
             }
 
-            transfer_to(program.clone(),
-                        token_program.clone(),
-                        token_mint.clone(),
-                        pool_account.clone(),
-                        pool_token_account.clone(),
-                        referrer_token_account.clone(),
-                        &pool_account_state,
-                        pool_account_state.reward_per_referral,
-                        pool_account_bump_seed)
-                .map_err(|_| AirdropPoolError::TransferToReferrerFailed)?;
-
+            let referral_reward = pool_account_state.get_referral_reward(depth);
+            // The referral reward vests on the referrer's own schedule, reserved until released, otherwise it is
+            // reserved until the referrer withdraws it. The reservation covers the transfer fee of the payout
+            let owed_amount = if is_vesting {
+                &mut referrer_account_state.vesting_amount
+            } else {
+                &mut referrer_account_state.pending_referral_rewards
+            };
+            let new_owed_amount = owed_amount
+                .checked_add(referral_reward)
+                .ok_or(AirdropPoolError::InsufficientBalance)?;
+            let reserved_amount = token::get_gross_amount_increase(transfer_fee.as_ref(), *owed_amount, new_owed_amount)
+                .ok_or(AirdropPoolError::InsufficientBalance)?;
+            pool_account_state.reserved_amount = pool_account_state.reserved_amount
+                .checked_add(reserved_amount)
+                .ok_or(AirdropPoolError::InsufficientBalance)?;
+            *owed_amount = new_owed_amount;
+            if depth == 1 {
+                referrer_account_state.direct_referrals += 1;
+            } else {
+                referrer_account_state.indirect_referrals += 1;
+            }
+            referrer_account_state.referral_rewards_earned += referral_reward;
+            // Reward mints reserve the referral rewards in their own pool reward token account
+            for (reward_mint_account, reward_mint_transfer_fee) in reward_mint_accounts.iter().zip(&reward_mint_transfer_fees) {
+                let pending_amount = &mut referrer_account_state.pending_reward_mint_referral_rewards[reward_mint_account.slot];
+                let new_pending_amount = pending_amount
+                    .checked_add(reward_mint_account.reward_mint.reward_per_referral)
+                    .ok_or(AirdropPoolError::InsufficientBalance)?;
+                let reserved_amount = token::get_gross_amount_increase(reward_mint_transfer_fee.as_ref(), *pending_amount, new_pending_amount)
+                    .ok_or(AirdropPoolError::InsufficientBalance)?;
+                let reward_mint = pool_account_state.reward_mints[reward_mint_account.slot].as_mut()
+                    .ok_or(AirdropPoolError::RewardMintKeyMismatch)?;
+                reward_mint.reserved_amount = reward_mint.reserved_amount
+                    .checked_add(reserved_amount)
+                    .ok_or(AirdropPoolError::InsufficientBalance)?;
+                *pending_amount = new_pending_amount;
+            }
+            referrer_account_state.pack_into(&mut referrer_account.data.borrow_mut()[..])?;
+            referral_rewards_paid += referral_reward;
 
             expected_referrer_wallet_id_option = referrer_account_state.referrer_wallet;
             depth += 1;
         }
     }
 
+    // New claimers usually don't hold the token yet, native SOL pools pay the claimer wallet itself
+    if !token::is_native(token_program.key) && claimer_token_account.data_is_empty() {
+        // Deployed clients create the claimer token account before claiming
+        let associated_token_program = associated_token_program.ok_or(AirdropPoolError::InitClaimerTokenAccountFailed)?;
+        init_claimer_token_account(payer,
+                                   system_program,
+                                   token_program,
+                                   associated_token_program,
+                                   token_mint,
+                                   claimer_wallet,
+                                   claimer_token_account)
+            .map_err(|_| AirdropPoolError::InitClaimerTokenAccountFailed)?;
+    }
+
     // println!("Init claimer");
-    init_claimer_account(claimer_wallet,
+    init_claimer_account(payer,
                          program,
                          system_program,
                          pool_account,
@@ -269,16 +434,156 @@ pub fn process_claim(
                          claimer_account_bump_seed)
         .map_err(|_| AirdropPoolError::InitClaimerAccountFailed)?;
 
+    let mut claimer_reward = reward_per_account;
+    if referrer.is_some() {
+        claimer_reward += pool_account_state.get_referral_reward(1);
+        referral_rewards_paid += pool_account_state.get_referral_reward(1);
+    }
+
     // println!("Update claimer account");
     let mut claimer_account_state: AirdropClaimer = AirdropClaimer::unpack(*claimer_account.data.borrow())?;
     claimer_account_state.claimed = 1;
-    claimer_account_state.referrer_wallet = referrer.clone();
-    claimer_account_state.pack_into(&mut &mut claimer_account.data.borrow_mut()[..])?;
+    claimer_account_state.referrer_wallet = referrer;
+    if is_vesting {
+        claimer_account_state.vesting_start_unix_timestamp = clock.unix_timestamp;
+        claimer_account_state.vesting_amount = claimer_reward;
+    }
+    claimer_account_state.pack_into(&mut claimer_account.data.borrow_mut()[..])?;
+
+    // Vesting rewards are reserved with their transfer fee until released, the pool must hold every reserved reward
+    if is_vesting {
+        let reserved_amount = token::get_gross_amount(transfer_fee.as_ref(), claimer_reward)
+            .ok_or(AirdropPoolError::InsufficientBalance)?;
+        pool_account_state.reserved_amount = pool_account_state.reserved_amount
+            .checked_add(reserved_amount)
+            .ok_or(AirdropPoolError::InsufficientBalance)?;
+        if pool_token_amount < pool_account_state.reserved_amount {
+            return Err(AirdropPoolError::InsufficientBalance.into());
+        }
+    }
 
     // println!("Reward claimer");
-    let mut claimer_reward = pool_account_state.reward_per_account;
-    if referrer.is_some() {
-        claimer_reward += pool_account_state.reward_per_referral;
+    if !is_vesting {
+        let transfer_amount = token::get_gross_amount(transfer_fee.as_ref(), claimer_reward)
+            .ok_or(AirdropPoolError::InsufficientBalance)?;
+        // The referral rewards credited above stay in the pool
+        if pool_account_state.get_available_amount(pool_token_amount) < transfer_amount {
+            return Err(AirdropPoolError::InsufficientBalance.into());
+        }
+        transfer_to(program.clone(),
+                    token_program.clone(),
+                    token_mint.clone(),
+                    pool_account.clone(),
+                    pool_token_account.clone(),
+                    claimer_token_account.clone(),
+                    &pool_account_state,
+                    transfer_amount,
+                    pool_account_bump_seed)
+            .map_err(|_| AirdropPoolError::TransferToUserFailed)?;
+    }
+
+    // Reward claimer with the reward mints, pools with reward mints don't vest. A claim pays every reward mint
+    // or nothing, so a single reward mint pool token account running short fails all claims
+
+    for (reward_mint_account, reward_mint_transfer_fee) in reward_mint_accounts.iter().zip(&reward_mint_transfer_fees) {
+        let mut reward_mint_reward = reward_mint_account.reward_mint.reward_per_account;
+        if referrer.is_some() {
+            reward_mint_reward += reward_mint_account.reward_mint.reward_per_referral;
+        }
+        if reward_mint_account.token_account.data_is_empty() {
+            let associated_token_program = associated_token_program.ok_or(AirdropPoolError::InitClaimerTokenAccountFailed)?;
+            init_claimer_token_account(payer,
+                                       system_program,
+                                       token_program,
+                                       associated_token_program,
+                                       reward_mint_account.token_mint,
+                                       claimer_wallet,
+                                       reward_mint_account.token_account)
+                .map_err(|_| AirdropPoolError::InitClaimerTokenAccountFailed)?;
+        }
+        let transfer_amount = token::get_gross_amount(reward_mint_transfer_fee.as_ref(), reward_mint_reward)
+            .ok_or(AirdropPoolError::InsufficientBalance)?;
+        // The referral rewards credited by this claim are already reserved
+        let reward_mint = pool_account_state.reward_mints[reward_mint_account.slot]
+            .ok_or(AirdropPoolError::RewardMintKeyMismatch)?;
+        if reward_mint.get_available_amount(token::get_amount(reward_mint_account.pool_token_account)?) < transfer_amount {
+            return Err(AirdropPoolError::InsufficientBalance.into());
+        }
+        transfer_to(program.clone(),
+                    token_program.clone(),
+                    reward_mint_account.token_mint.clone(),
+                    pool_account.clone(),
+                    reward_mint_account.pool_token_account.clone(),
+                    reward_mint_account.token_account.clone(),
+                    &pool_account_state,
+                    transfer_amount,
+                    pool_account_bump_seed)
+            .map_err(|_| AirdropPoolError::TransferToUserFailed)?;
+    }
+
+    // Update statistics
+
+    pool_account_state.total_claimers += 1;
+    pool_account_state.total_base_rewards_paid += reward_per_account;
+    pool_account_state.total_referral_rewards_paid += referral_rewards_paid;
+    pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_release(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let ReleaseAccounts {
+        program,
+        clock_sysvar,
+        token_program,
+        token_mint,
+        pool_account,
+        pool_token_account,
+        claimer_account,
+        claimer_token_account,
+        mut pool_account_state,
+        pool_account_bump_seed,
+        ..
+    } = ReleaseAccounts::parse(program_id, accounts)?;
+
+    // Unpack states
+
+    let clock = Clock::from_account_info(clock_sysvar)?;
+    let mut claimer_account_state: AirdropClaimer = AirdropClaimer::unpack(*claimer_account.data.borrow())?;
+
+    // Validate state
+
+    if pool_account_state.vesting_duration_seconds.is_none() {
+        return Err(AirdropPoolError::VestingNotEnabled.into());
+    }
+    let amount = vesting::get_releasable_amount(&pool_account_state, &claimer_account_state, clock.unix_timestamp);
+    if amount == 0 {
+        return Err(AirdropPoolError::NothingToRelease.into());
+    }
+
+    // Release, the reservation was made with the transfer fee so the reserved amount drops by the gross amount
+
+    let transfer_fee = token::get_transfer_fee(token_program, token_mint)?;
+    let released_amount = claimer_account_state.released_amount + amount;
+    let unreserved_amount = token::get_gross_amount_increase(transfer_fee.as_ref(),
+                                                             claimer_account_state.released_amount,
+                                                             released_amount)
+        .ok_or(AirdropPoolError::InsufficientBalance)?;
+    claimer_account_state.released_amount = released_amount;
+    claimer_account_state.pack_into(&mut claimer_account.data.borrow_mut()[..])?;
+
+    pool_account_state.reserved_amount = pool_account_state.reserved_amount.saturating_sub(unreserved_amount);
+    pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
+
+    // A transfer fee rounded up on each release must not take from the rewards reserved for others
+    let transfer_amount = token::get_gross_amount(transfer_fee.as_ref(), amount)
+        .ok_or(AirdropPoolError::InsufficientBalance)?;
+    let pool_token_amount = token::get_pool_amount(token_program, pool_account, pool_token_account)?;
+    if pool_account_state.get_available_amount(pool_token_amount) < transfer_amount {
+        return Err(AirdropPoolError::InsufficientBalance.into());
     }
     transfer_to(program.clone(),
                 token_program.clone(),
@@ -287,27 +592,679 @@ pub fn process_claim(
                 pool_token_account.clone(),
                 claimer_token_account.clone(),
                 &pool_account_state,
-                claimer_reward,
+                transfer_amount,
                 pool_account_bump_seed)
         .map_err(|_| AirdropPoolError::TransferToUserFailed)?;
 
     Ok(())
 }
 
+pub fn process_withdraw_referral_rewards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let WithdrawReferralRewardsAccounts {
+        program,
+        token_program,
+        token_mint,
+        pool_account,
+        pool_token_account,
+        referrer_account,
+        referrer_token_account,
+        reward_mint_accounts,
+        mut pool_account_state,
+        pool_account_bump_seed,
+        ..
+    } = WithdrawReferralRewardsAccounts::parse(program_id, accounts)?;
+
+    // Unpack states
+
+    let mut referrer_account_state: AirdropClaimer = AirdropClaimer::unpack(*referrer_account.data.borrow())?;
+
+    // Validate state
+
+    let amount = referrer_account_state.pending_referral_rewards;
+    let has_reward_mint_rewards = reward_mint_accounts.iter()
+        .any(|reward_mint_account| referrer_account_state.pending_reward_mint_referral_rewards[reward_mint_account.slot] > 0);
+    if amount == 0 && !has_reward_mint_rewards {
+        return Err(AirdropPoolError::NoPendingReferralRewards.into());
+    }
+
+    // Withdraw, the referral rewards of each mint
+
+    // The pending referral rewards of each mint were reserved with the transfer fee of their payout
+    let mut withdrawals = vec![(token_mint, pool_token_account, referrer_token_account, amount)];
+    for reward_mint_account in &reward_mint_accounts {
+        let reward_mint_amount = referrer_account_state.pending_reward_mint_referral_rewards[reward_mint_account.slot];
+        withdrawals.push((reward_mint_account.token_mint,
+                          reward_mint_account.pool_token_account,
+                          reward_mint_account.token_account,
+                          reward_mint_amount));
+        referrer_account_state.pending_reward_mint_referral_rewards[reward_mint_account.slot] = 0;
+
+        let reward_mint_transfer_fee = token::get_transfer_fee(token_program, reward_mint_account.token_mint)?;
+        let unreserved_amount = token::get_gross_amount(reward_mint_transfer_fee.as_ref(), reward_mint_amount)
+            .ok_or(AirdropPoolError::InsufficientBalance)?;
+        let reward_mint = pool_account_state.reward_mints[reward_mint_account.slot].as_mut()
+            .ok_or(AirdropPoolError::RewardMintKeyMismatch)?;
+        reward_mint.reserved_amount = reward_mint.reserved_amount.saturating_sub(unreserved_amount);
+    }
+    referrer_account_state.pending_referral_rewards = 0;
+    referrer_account_state.pack_into(&mut referrer_account.data.borrow_mut()[..])?;
+
+    let transfer_fee = token::get_transfer_fee(token_program, token_mint)?;
+    let unreserved_amount = token::get_gross_amount(transfer_fee.as_ref(), amount)
+        .ok_or(AirdropPoolError::InsufficientBalance)?;
+    pool_account_state.reserved_amount = pool_account_state.reserved_amount.saturating_sub(unreserved_amount);
+    pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
+
+    for (withdrawal_token_mint, withdrawal_pool_token_account, withdrawal_token_account, withdrawal_amount) in withdrawals {
+        if withdrawal_amount == 0 {
+            continue;
+        }
+        let transfer_fee = token::get_transfer_fee(token_program, withdrawal_token_mint)?;
+        let transfer_amount = token::get_gross_amount(transfer_fee.as_ref(), withdrawal_amount)
+            .ok_or(AirdropPoolError::InsufficientBalance)?;
+        if token::get_pool_amount(token_program, pool_account, withdrawal_pool_token_account)? < transfer_amount {
+            return Err(AirdropPoolError::InsufficientBalance.into());
+        }
+        transfer_to(program.clone(),
+                    token_program.clone(),
+                    withdrawal_token_mint.clone(),
+                    pool_account.clone(),
+                    withdrawal_pool_token_account.clone(),
+                    withdrawal_token_account.clone(),
+                    &pool_account_state,
+                    transfer_amount,
+                    pool_account_bump_seed)
+            .map_err(|_| AirdropPoolError::TransferToReferrerFailed)?;
+    }
+
+    Ok(())
+}
+
+pub fn process_deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let DepositAccounts {
+        depositor_wallet,
+        program,
+        rent_sysvar,
+        system_program,
+        token_program,
+        token_mint,
+        pool_account,
+        pool_token_account,
+        depositor_token_account,
+        depositor_account,
+        mut pool_account_state,
+        depositor_account_bump_seed,
+    } = DepositAccounts::parse(program_id, accounts)?;
+
+    // Unpack states
+
+    let rent = Rent::from_account_info(rent_sysvar)?;
+
+    // Validate parameters
+
+    if amount == 0 {
+        return Err(AirdropPoolError::InvalidDepositAmount.into());
+    }
+
+    // Deposit
+
+    let pool_token_amount = token::get_pool_amount(token_program, pool_account, pool_token_account)?;
+    if token::is_native(token_program.key) {
+        invoke(
+            &system_instruction::transfer(depositor_wallet.key, pool_account.key, amount),
+            &[
+                depositor_wallet.clone(),
+                pool_account.clone(),
+                system_program.clone(),
+            ],
+        ).map_err(|_| AirdropPoolError::TransferToPoolFailed)?;
+    } else {
+        invoke(
+            &token::transfer_instruction(
+                token_program,
+                depositor_token_account.key,
+                token_mint,
+                pool_token_account.key,
+                depositor_wallet.key,
+                &[],
+                amount,
+            )?,
+            &[
+                depositor_token_account.clone(),
+                token_mint.clone(),
+                pool_token_account.clone(),
+                depositor_wallet.clone(),
+                token_program.clone(),
+            ],
+        ).map_err(|_| AirdropPoolError::TransferToPoolFailed)?;
+    }
+
+    // The ledger records what the pool received, net of any transfer fee
+    let amount = token::get_pool_amount(token_program, pool_account, pool_token_account)? - pool_token_amount;
+
+    // Update ledger
+
+    if depositor_account.data_is_empty() {
+        init_depositor_account(program,
+                               system_program,
+                               pool_account,
+                               depositor_wallet,
+                               depositor_account,
+                               &rent,
+                               depositor_account_bump_seed)
+            .map_err(|_| AirdropPoolError::InitDepositorAccountFailed)?;
+    }
+
+    let mut depositor_account_state: AirdropDepositor = AirdropDepositor::unpack(*depositor_account.data.borrow())?;
+    depositor_account_state.total_deposited += amount;
+    depositor_account_state.pack_into(&mut depositor_account.data.borrow_mut()[..])?;
+
+    pool_account_state.total_deposited += amount;
+    pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Upgrades the pool and the given claimers of the pool to the current layouts, [payer] funds the
+// additional rent
+pub fn process_migrate_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let MigratePoolAccounts {
+        payer,
+        system_program,
+        pool_account,
+        upgrade_authority,
+        claimer_accounts,
+        mut pool_account_state,
+        is_pool_migrated,
+        ..
+    } = MigratePoolAccounts::parse(program_id, accounts)?;
+
+    // Migrate pool account, pools created before the authority get the upgrade authority of the program
+
+    if let Some(upgrade_authority) = upgrade_authority {
+        pool_account_state.authority = *upgrade_authority.key;
+    }
+    if !is_pool_migrated {
+        migrate_account(payer, system_program, pool_account, AirdropPool::PACKED_SIZE)?;
+        pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
+    }
+
+    // Migrate claimer accounts
+
+    for claimer_account in claimer_accounts {
+        let claimer_account_state: AirdropClaimer = AirdropClaimer::unpack(*claimer_account.data.borrow())?;
+        migrate_account(payer, system_program, claimer_account, AirdropClaimer::PACKED_SIZE)?;
+        claimer_account_state.pack_into(&mut claimer_account.data.borrow_mut()[..])?;
+    }
+
+    Ok(())
+}
+
+// The claimer account is what prevents double claims, so it can only be closed once the pool
+// is closed or its claim window has ended
+pub fn process_close_claimer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let CloseClaimerAccounts {
+        clock_sysvar,
+        pool_account,
+        claimer_wallet,
+        claimer_account,
+        ..
+    } = CloseClaimerAccounts::parse(program_id, accounts)?;
+
+    // Unpack states
+
+    let clock = Clock::from_account_info(clock_sysvar)?;
+    let claimer_account_state: AirdropClaimer = AirdropClaimer::unpack(*claimer_account.data.borrow())?;
+
+    // Validate state
+
+    let is_pool_closed = pool_account.owner != program_id || pool_account.data_is_empty();
+    if !is_pool_closed {
+        let pool_account_state: AirdropPool = AirdropPool::unpack(*pool_account.data.borrow())?;
+        let is_pool_ended = match pool_account_state.claim_end_unix_timestamp {
+            Some(claim_end) => clock.unix_timestamp >= claim_end,
+            None => false,
+        };
+        if !is_pool_ended {
+            return Err(AirdropPoolError::PoolStillActive.into());
+        }
+        // Rewards can still be withdrawn from an ended pool
+        if claimer_account_state.pending_referral_rewards > 0
+            || claimer_account_state.pending_reward_mint_referral_rewards.iter().any(|&amount| amount > 0)
+            || claimer_account_state.released_amount < claimer_account_state.vesting_amount {
+            return Err(AirdropPoolError::ClaimerHasPendingRewards.into());
+        }
+    }
+
+    // Close claimer account
+
+    close_account(claimer_account, claimer_wallet)?;
+
+    Ok(())
+}
+
+pub fn process_update_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reward_per_account: u64,
+    reward_per_referral: u64,
+    max_referral_depth: u8,
+) -> ProgramResult {
+    let UpdatePoolAccounts {
+        pool_account,
+        mut pool_account_state,
+        ..
+    } = UpdatePoolAccounts::parse(program_id, accounts)?;
+
+    // Validate parameters
+
+    // The referral rewards table holds the rewards of the depth the pool was initialized with
+    if pool_account_state.referral_rewards.is_some() && max_referral_depth != pool_account_state.max_referral_depth {
+        return Err(AirdropPoolError::InvalidReferralRewards.into());
+    }
+    if token::is_native(&pool_account_state.token_program_id)
+        && pool_account_state.merkle_root.is_none()
+        && reward_per_account < Rent::get()?.minimum_balance(0) {
+        return Err(AirdropPoolError::RewardBelowRentExemption.into());
+    }
+
+    // Update pool account
+
+    pool_account_state.reward_per_account = reward_per_account;
+    pool_account_state.reward_per_referral = reward_per_referral;
+    pool_account_state.max_referral_depth = max_referral_depth;
+    pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    paused: u8,
+) -> ProgramResult {
+    let UpdatePoolAccounts {
+        pool_account,
+        mut pool_account_state,
+        ..
+    } = UpdatePoolAccounts::parse(program_id, accounts)?;
+
+    // Update pool account
+
+    pool_account_state.paused = paused;
+    pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let WithdrawAccounts {
+        program,
+        token_program,
+        token_mint,
+        pool_account,
+        pool_token_account,
+        destination_token_account,
+        reward_mint_accounts,
+        pool_account_state,
+        pool_account_bump_seed,
+        ..
+    } = WithdrawAccounts::parse(program_id, accounts)?;
+
+    // Unpack states
+
+    let pool_token_amount = token::get_pool_amount(token_program, pool_account, pool_token_account)?;
+
+    // Validate state
+
+    if pool_account_state.get_available_amount(pool_token_amount) < amount {
+        return Err(AirdropPoolError::InsufficientBalance.into());
+    }
+
+    // Withdraw
+
+    transfer_to(program.clone(),
+                token_program.clone(),
+                token_mint.clone(),
+                pool_account.clone(),
+                pool_token_account.clone(),
+                destination_token_account.clone(),
+                &pool_account_state,
+                amount,
+                pool_account_bump_seed)
+        .map_err(|_| AirdropPoolError::TransferToDestinationFailed)?;
+
+    // Empty the given pool reward token accounts, except the referral rewards they reserve
+
+    for reward_mint_account in &reward_mint_accounts {
+        let reward_mint = pool_account_state.reward_mints[reward_mint_account.slot]
+            .ok_or(AirdropPoolError::RewardMintKeyMismatch)?;
+        let available_amount = reward_mint.get_available_amount(token::get_amount(reward_mint_account.pool_token_account)?);
+        if available_amount > 0 {
+            transfer_to(program.clone(),
+                        token_program.clone(),
+                        reward_mint_account.token_mint.clone(),
+                        pool_account.clone(),
+                        reward_mint_account.pool_token_account.clone(),
+                        reward_mint_account.token_account.clone(),
+                        &pool_account_state,
+                        available_amount,
+                        pool_account_bump_seed)
+                .map_err(|_| AirdropPoolError::TransferToDestinationFailed)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn process_distribute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amounts: Vec<u64>,
+) -> ProgramResult {
+    let DistributeAccounts {
+        authority,
+        program,
+        rent_sysvar,
+        clock_sysvar,
+        system_program,
+        token_program,
+        associated_token_program,
+        token_mint,
+        pool_account,
+        pool_token_account,
+        recipients,
+        mut pool_account_state,
+        pool_account_bump_seed,
+    } = DistributeAccounts::parse(program_id, accounts)?;
+
+    // Unpack states
+
+    let rent = Rent::from_account_info(rent_sysvar)?;
+    let clock = Clock::from_account_info(clock_sysvar)?;
+    let pool_token_amount = token::get_pool_amount(token_program, pool_account, pool_token_account)?;
+
+    // Validate state
+
+    if amounts.len() != recipients.len() {
+        return Err(AirdropPoolError::DistributeAmountsMismatch.into());
+    }
+    // Native SOL pools pay the recipient wallets, which stay below the rent exemption otherwise
+    if token::is_native(token_program.key) && amounts.iter().any(|amount| *amount < rent.minimum_balance(0)) {
+        return Err(AirdropPoolError::RewardBelowRentExemption.into());
+    }
+    let transfer_fee = token::get_transfer_fee(token_program, token_mint)?;
+    let total_amount = amounts.iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
+        .ok_or(AirdropPoolError::InsufficientBalance)?;
+    // Each recipient is paid or reserved with the transfer fee of its own amount
+    let total_transfer_amount = amounts.iter()
+        .try_fold(0u64, |total, amount| total.checked_add(token::get_gross_amount(transfer_fee.as_ref(), *amount)?))
+        .ok_or(AirdropPoolError::InsufficientBalance)?;
+    if pool_account_state.get_available_amount(pool_token_amount) < total_transfer_amount {
+        return Err(AirdropPoolError::InsufficientBalance.into());
+    }
+
+    let is_vesting = pool_account_state.vesting_duration_seconds.is_some();
+
+    // Distribute, the authority funds the accounts of the recipients
+
+    for (recipient, amount) in recipients.iter().zip(amounts) {
+        if !token::is_native(token_program.key) && recipient.token_account.data_is_empty() {
+            init_claimer_token_account(authority,
+                                       system_program,
+                                       token_program,
+                                       associated_token_program,
+                                       token_mint,
+                                       recipient.wallet,
+                                       recipient.token_account)
+                .map_err(|_| AirdropPoolError::InitClaimerTokenAccountFailed)?;
+        }
+
+        init_claimer_account(authority,
+                             program,
+                             system_program,
+                             pool_account,
+                             recipient.wallet,
+                             recipient.claimer_account,
+                             &rent,
+                             recipient.claimer_account_bump_seed)
+            .map_err(|_| AirdropPoolError::InitClaimerAccountFailed)?;
+
+        // Recipients can't also claim
+        let mut claimer_account_state: AirdropClaimer = AirdropClaimer::unpack(*recipient.claimer_account.data.borrow())?;
+        claimer_account_state.claimed = 1;
+        if is_vesting {
+            claimer_account_state.vesting_start_unix_timestamp = clock.unix_timestamp;
+            claimer_account_state.vesting_amount = amount;
+        }
+        claimer_account_state.pack_into(&mut recipient.claimer_account.data.borrow_mut()[..])?;
+
+        // Vesting amounts are reserved with their transfer fee until released
+        let transfer_amount = token::get_gross_amount(transfer_fee.as_ref(), amount)
+            .ok_or(AirdropPoolError::InsufficientBalance)?;
+        if is_vesting {
+            pool_account_state.reserved_amount = pool_account_state.reserved_amount
+                .checked_add(transfer_amount)
+                .ok_or(AirdropPoolError::InsufficientBalance)?;
+        } else {
+            transfer_to(program.clone(),
+                        token_program.clone(),
+                        token_mint.clone(),
+                        pool_account.clone(),
+                        pool_token_account.clone(),
+                        recipient.token_account.clone(),
+                        &pool_account_state,
+                        transfer_amount,
+                        pool_account_bump_seed)
+                .map_err(|_| AirdropPoolError::TransferToUserFailed)?;
+        }
+    }
+
+    // Update statistics
+
+    pool_account_state.total_claimers += recipients.len() as u64;
+    pool_account_state.total_base_rewards_paid += total_amount;
+    pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_add_reward_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reward_per_account: u64,
+    reward_per_referral: u64,
+) -> ProgramResult {
+    let AddRewardMintAccounts {
+        authority,
+        program,
+        rent_sysvar,
+        system_program,
+        token_program,
+        reward_mint,
+        pool_account,
+        pool_reward_token_account,
+        mut pool_account_state,
+        pool_reward_token_account_bump_seed,
+    } = AddRewardMintAccounts::parse(program_id, accounts)?;
+
+    // Unpack states
+
+    let rent = Rent::from_account_info(rent_sysvar)?;
+
+    // Validate state
+
+    // Vesting only tracks the pool mint
+    if pool_account_state.vesting_duration_seconds.is_some() {
+        return Err(AirdropPoolError::RewardMintsNotSupported.into());
+    }
+    let is_reward_mint_added = pool_account_state.get_reward_mints().iter()
+        .any(|(_, added_reward_mint)| added_reward_mint.token_mint_id == *reward_mint.key);
+    if *reward_mint.key == pool_account_state.token_mint_id || is_reward_mint_added {
+        return Err(AirdropPoolError::RewardMintAlreadyAdded.into());
+    }
+    let slot = pool_account_state.reward_mints.iter()
+        .position(Option::is_none)
+        .ok_or(AirdropPoolError::TooManyRewardMints)?;
+
+    // Init pool reward token account
+
+    create_pool_owned_token_account(authority,
+                                    system_program,
+                                    token_program,
+                                    reward_mint,
+                                    pool_reward_token_account,
+                                    &rent,
+                                    pool_reward_token_account_seeds!(program.key, pool_account.key, reward_mint.key, pool_reward_token_account_bump_seed))
+        .and_then(|_| initialize_pool_owned_token_account(rent_sysvar, token_program, reward_mint, pool_account, pool_reward_token_account))
+        .map_err(|_| AirdropPoolError::InitPoolTokenAccountFailed)?;
+
+    // Update pool account
+
+    pool_account_state.reward_mints[slot] = Some(RewardMint {
+        token_mint_id: *reward_mint.key,
+        reward_per_account,
+        reward_per_referral,
+        reserved_amount: 0,
+    });
+    pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn process_close_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let ClosePoolAccounts {
+        program,
+        token_program,
+        token_mint,
+        pool_account,
+        pool_token_account,
+        destination_token_account,
+        destination_wallet,
+        reward_mint_accounts,
+        pool_account_state,
+        pool_account_bump_seed,
+        ..
+    } = ClosePoolAccounts::parse(program_id, accounts)?;
+
+    // Unpack states
+
+    let pool_token_amount = token::get_pool_amount(token_program, pool_account, pool_token_account)?;
+
+    // Validate state
+
+    let has_reserved_reward_mint_rewards = pool_account_state.get_reward_mints().iter()
+        .any(|(_, reward_mint)| reward_mint.reserved_amount > 0);
+    if pool_account_state.reserved_amount > 0 || has_reserved_reward_mint_rewards {
+        return Err(AirdropPoolError::PoolHasReservedRewards.into());
+    }
+
+    // Send the remaining tokens to the destination
+
+    if pool_token_amount > 0 {
+        transfer_to(program.clone(),
+                    token_program.clone(),
+                    token_mint.clone(),
+                    pool_account.clone(),
+                    pool_token_account.clone(),
+                    destination_token_account.clone(),
+                    &pool_account_state,
+                    pool_token_amount,
+                    pool_account_bump_seed)
+            .map_err(|_| AirdropPoolError::TransferToDestinationFailed)?;
+    }
+
+    // Close program token account, native SOL pools don't have one
+
+    if !token::is_native(token_program.key) {
+        if token::get_transfer_fee(token_program, token_mint)?.is_some() {
+            harvest_withheld_tokens(token_program.clone(),
+                                    token_mint.clone(),
+                                    pool_token_account.clone())
+                .map_err(|_| AirdropPoolError::ClosePoolTokenAccountFailed)?;
+        }
+
+        close_pool_token_account(program.clone(),
+                                 token_program.clone(),
+                                 pool_account.clone(),
+                                 pool_token_account.clone(),
+                                 destination_wallet.clone(),
+                                 &pool_account_state,
+                                 pool_account_bump_seed)
+            .map_err(|_| AirdropPoolError::ClosePoolTokenAccountFailed)?;
+    }
+
+    // Empty and close the pool reward token accounts
+
+    for reward_mint_account in &reward_mint_accounts {
+        let pool_reward_token_amount = token::get_amount(reward_mint_account.pool_token_account)?;
+        if pool_reward_token_amount > 0 {
+            transfer_to(program.clone(),
+                        token_program.clone(),
+                        reward_mint_account.token_mint.clone(),
+                        pool_account.clone(),
+                        reward_mint_account.pool_token_account.clone(),
+                        reward_mint_account.token_account.clone(),
+                        &pool_account_state,
+                        pool_reward_token_amount,
+                        pool_account_bump_seed)
+                .map_err(|_| AirdropPoolError::TransferToDestinationFailed)?;
+        }
+
+        if token::get_transfer_fee(token_program, reward_mint_account.token_mint)?.is_some() {
+            harvest_withheld_tokens(token_program.clone(),
+                                    reward_mint_account.token_mint.clone(),
+                                    reward_mint_account.pool_token_account.clone())
+                .map_err(|_| AirdropPoolError::ClosePoolTokenAccountFailed)?;
+        }
+
+        close_pool_token_account(program.clone(),
+                                 token_program.clone(),
+                                 pool_account.clone(),
+                                 reward_mint_account.pool_token_account.clone(),
+                                 destination_wallet.clone(),
+                                 &pool_account_state,
+                                 pool_account_bump_seed)
+            .map_err(|_| AirdropPoolError::ClosePoolTokenAccountFailed)?;
+    }
+
+    // Close program account
+
+    close_account(pool_account, destination_wallet)?;
+
+    Ok(())
+}
+
 // Utils
 
 pub fn init_pool_account<'a>(
     funder: &AccountInfo<'a>,
     program: &AccountInfo<'a>,
     system_program: &AccountInfo<'a>,
-    token_program: &AccountInfo<'a>,
-    token_mint: &AccountInfo<'a>,
     pool_account: &AccountInfo<'a>,
     rent: &Rent,
-    pool_account_nonce: [u8; 4],
-    reward_per_account: u64,
-    reward_per_referral: u64,
-    max_referral_depth: u8,
+    pool_account_state: &AirdropPool,
     pool_account_bump_seed: u8,
 ) -> ProgramResult {
     // Create account
@@ -325,78 +1282,70 @@ pub fn init_pool_account<'a>(
             system_program.clone(),
         ],
         &[
-            pool_account_seeds!(program.key, token_mint.key, &pool_account_nonce, pool_account_bump_seed),
+            pool_account_seeds!(program.key, pool_account_state.token_mint_id, &pool_account_state.account_nonce, pool_account_bump_seed),
         ],
     )?;
 
     // Initialize account
-    AirdropPool {
-        token_program_id: token_program.key.clone(),
-        token_mint_id: token_mint.key.clone(),
-        account_nonce: pool_account_nonce,
-        reward_per_account,
-        reward_per_referral,
-        max_referral_depth,
-    }.pack_into(&mut &mut pool_account.data.borrow_mut()[..])?;
+    pool_account_state.pack_into(&mut pool_account.data.borrow_mut()[..])?;
 
     Ok(())
 }
 
-pub fn init_pool_token_account<'a>(
+// Creates a token account at the address derived from [token_account_seeds], to be owned by the pool
+fn create_pool_owned_token_account<'a>(
     funder: &AccountInfo<'a>,
-    program: &AccountInfo<'a>,
-    rent_sysvar: &AccountInfo<'a>,
     system_program: &AccountInfo<'a>,
     token_program: &AccountInfo<'a>,
     token_mint: &AccountInfo<'a>,
-    pool_account: &AccountInfo<'a>,
-    pool_token_account: &AccountInfo<'a>,
+    token_account: &AccountInfo<'a>,
     rent: &Rent,
-    pool_token_account_bump_seed: u8,
+    token_account_seeds: &[&[u8]],
 ) -> ProgramResult {
-    // Create account
+    let token_account_len = token::get_account_len(token_program, token_mint)?;
     invoke_signed(
         &system_instruction::create_account(
             funder.key,
-            pool_token_account.key,
-            rent.minimum_balance(spl_token::state::Account::LEN).max(1),
-            spl_token::state::Account::LEN as u64,
+            token_account.key,
+            rent.minimum_balance(token_account_len).max(1),
+            token_account_len as u64,
             token_program.key,
         ),
         &[
             funder.clone(),
-            rent_sysvar.clone(),
-            pool_token_account.clone(),
+            token_account.clone(),
             system_program.clone(),
             token_program.clone(),
         ],
         &[
-            pool_token_account_seeds!(program.key, pool_account.key, pool_token_account_bump_seed),
+            token_account_seeds,
         ],
-    )?;
+    )
+}
 
-    // Initialize account
+fn initialize_pool_owned_token_account<'a>(
+    rent_sysvar: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    token_mint: &AccountInfo<'a>,
+    pool_account: &AccountInfo<'a>,
+    token_account: &AccountInfo<'a>,
+) -> ProgramResult {
     invoke_signed(
-        &spl_token::instruction::initialize_account(
+        &spl_token_2022::instruction::initialize_account(
             token_program.key,
-            pool_token_account.key,
+            token_account.key,
             token_mint.key,
             pool_account.key,
         )?,
         &[
             rent_sysvar.clone(),
-            pool_token_account.clone(),
+            token_account.clone(),
             pool_account.clone(),
             token_mint.clone(),
             token_program.clone(),
         ],
-        &[
-            // pool_account_seeds!(program.key, token_mint.key, &pool_account_nonce, pool_account_bump_seed),
-            // pool_token_account_seeds!(program.key, pool_account.key, pool_token_account_bump_seed),
-        ],
-    )?;
-
-    Ok(())
+        &[],
+    )
 }
 
 pub fn init_claimer_account<'a>(
@@ -429,14 +1378,78 @@ pub fn init_claimer_account<'a>(
     )?;
 
     // Initialize account
-    AirdropClaimer {
-        referrer_wallet: None,
-        claimed: 0,
-    }.pack_into(&mut &mut claimer_account.data.borrow_mut()[..])?;
+    AirdropClaimer::default().pack_into(&mut claimer_account.data.borrow_mut()[..])?;
 
     Ok(())
 }
 
+// The depositor funds its own ledger account
+pub fn init_depositor_account<'a>(
+    program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    pool_account: &AccountInfo<'a>,
+    depositor_wallet: &AccountInfo<'a>,
+    depositor_account: &AccountInfo<'a>,
+    rent: &Rent,
+    depositor_account_bump_seed: u8,
+) -> ProgramResult {
+    // Create account
+    invoke_signed(
+        &system_instruction::create_account(
+            depositor_wallet.key,
+            depositor_account.key,
+            rent.minimum_balance(AirdropDepositor::PACKED_SIZE).max(1),
+            AirdropDepositor::PACKED_SIZE as u64,
+            program.key,
+        ),
+        &[
+            depositor_wallet.clone(),
+            depositor_account.clone(),
+            system_program.clone(),
+        ],
+        &[
+            depositor_account_seeds!(program.key, pool_account.key, depositor_wallet.key, depositor_account_bump_seed),
+        ],
+    )?;
+
+    // Initialize account
+    AirdropDepositor {
+        pool_account: *pool_account.key,
+        depositor_wallet: *depositor_wallet.key,
+        total_deposited: 0,
+    }.pack_into(&mut depositor_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn init_claimer_token_account<'a>(
+    funder: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    associated_token_program: &AccountInfo<'a>,
+    token_mint: &AccountInfo<'a>,
+    claimer_wallet: &AccountInfo<'a>,
+    claimer_token_account: &AccountInfo<'a>,
+) -> ProgramResult {
+    invoke(
+        &spl_associated_token_account::instruction::create_associated_token_account(
+            funder.key,
+            claimer_wallet.key,
+            token_mint.key,
+            token_program.key,
+        ),
+        &[
+            funder.clone(),
+            claimer_token_account.clone(),
+            claimer_wallet.clone(),
+            token_mint.clone(),
+            system_program.clone(),
+            token_program.clone(),
+            associated_token_program.clone(),
+        ],
+    )
+}
+
 pub fn transfer_to<'a>(
     program: AccountInfo<'a>,
     token_program: AccountInfo<'a>,
@@ -448,19 +1461,129 @@ pub fn transfer_to<'a>(
     amount: u64,
     pool_account_bump_seed: u8,
 ) -> ProgramResult {
-    let ix = spl_token::instruction::transfer(
-        token_program.key,
+    // Native SOL pools pay from the pool account, the system program can't transfer from an account
+    // holding data, so the program moves the lamports of the pool account it owns directly
+    if token::is_native(token_program.key) {
+        return transfer_lamports(&pool_account, &destination, amount);
+    }
+    let ix = token::transfer_instruction(
+        &token_program,
         pool_token_account.key,
+        &token_mint,
         destination.key,
         pool_account.key,
         &[pool_account.key],
         amount,
     )?;
+    invoke_signed(
+        &ix,
+        &[pool_token_account.clone(), token_mint.clone(), destination.clone(), pool_account.clone(), token_program.clone()],
+        &[
+            // Pool reward token accounts are owned by the pool too, whose seeds use the pool mint
+            pool_account_seeds!(program.key, pool_account_state.token_mint_id, &pool_account_state.account_nonce, pool_account_bump_seed),
+        ],
+    )
+}
+
+pub fn close_pool_token_account<'a>(
+    program: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    pool_account: AccountInfo<'a>,
+    pool_token_account: AccountInfo<'a>,
+    destination: AccountInfo<'a>,
+    pool_account_state: &AirdropPool,
+    pool_account_bump_seed: u8,
+) -> ProgramResult {
+    let ix = spl_token_2022::instruction::close_account(
+        token_program.key,
+        pool_token_account.key,
+        destination.key,
+        pool_account.key,
+        &[pool_account.key],
+    )?;
     invoke_signed(
         &ix,
         &[pool_token_account.clone(), destination.clone(), pool_account.clone(), token_program.clone()],
         &[
-            pool_account_seeds!(program.key, token_mint.key, &pool_account_state.account_nonce, pool_account_bump_seed),
+            pool_account_seeds!(program.key, pool_account_state.token_mint_id, &pool_account_state.account_nonce, pool_account_bump_seed),
         ],
     )
+}
+
+// Moves the transfer fees withheld in the pool token account to the mint, token accounts can't be closed while they hold some
+pub fn harvest_withheld_tokens<'a>(
+    token_program: AccountInfo<'a>,
+    token_mint: AccountInfo<'a>,
+    pool_token_account: AccountInfo<'a>,
+) -> ProgramResult {
+    let ix = spl_token_2022::extension::transfer_fee::instruction::harvest_withheld_tokens_to_mint(
+        token_program.key,
+        token_mint.key,
+        &[pool_token_account.key],
+    )?;
+    invoke(
+        &ix,
+        &[token_mint.clone(), pool_token_account.clone(), token_program.clone()],
+    )
+}
+
+// Resizes a program owned account to [data_len], [payer] funds its rent exemption
+pub fn migrate_account<'a>(
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    data_len: usize,
+) -> ProgramResult {
+    let rent = Rent::get()?;
+    let required_lamports = rent.minimum_balance(data_len).max(1)
+        .saturating_sub(account.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[
+                payer.clone(),
+                account.clone(),
+                system_program.clone(),
+            ],
+        ).map_err(|_| AirdropPoolError::MigratePoolFailed)?;
+    }
+    account.realloc(data_len, true)
+        .map_err(|_| AirdropPoolError::MigratePoolFailed)?;
+    Ok(())
+}
+
+// Moves lamports out of a program owned account, which keeps its rent exemption
+pub fn transfer_lamports<'a>(
+    source: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    if token::get_native_amount(source)? < amount {
+        return Err(AirdropPoolError::InsufficientBalance.into());
+    }
+    let source_lamports = source.lamports();
+    **source.lamports.borrow_mut() = source_lamports - amount;
+    let destination_lamports = destination.lamports();
+    **destination.lamports.borrow_mut() = destination_lamports
+        .checked_add(amount)
+        .ok_or(AirdropPoolError::TransferToDestinationFailed)?;
+    Ok(())
+}
+
+// Drains a program owned account into [destination], the runtime deletes it after the transaction
+pub fn close_account<'a>(
+    account: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+) -> ProgramResult {
+    let destination_lamports = destination.lamports();
+    **destination.lamports.borrow_mut() = destination_lamports
+        .checked_add(account.lamports())
+        .ok_or(AirdropPoolError::TransferToDestinationFailed)?;
+    **account.lamports.borrow_mut() = 0;
+
+    for byte in account.data.borrow_mut().iter_mut() {
+        *byte = 0;
+    }
+
+    Ok(())
 }
\ No newline at end of file
@@ -1,9 +1,23 @@
 use borsh::BorshDeserialize;
 use borsh::BorshSchema;
 use borsh::BorshSerialize;
+use solana_program::clock::UnixTimestamp;
 use solana_program::pubkey::Pubkey;
 use crate::packable::Packable;
 
+pub const MAX_REFERRAL_DEPTH: usize = 8;
+pub const MAX_REWARD_MINTS: usize = 3;
+
+// Discriminators of the account layouts, a new layout of an account type takes a new discriminator
+pub const AIRDROP_POOL_V1: u8 = 1;
+pub const AIRDROP_CLAIMER_V1: u8 = 2;
+pub const AIRDROP_DEPOSITOR_V1: u8 = 3;
+
+// Zeroed bytes after the fields of pools and claimers. New fields are appended there and read as 0 or None
+// from existing accounts, so they don't need a new layout
+pub const AIRDROP_POOL_RESERVED_SIZE: usize = 128;
+pub const AIRDROP_CLAIMER_RESERVED_SIZE: usize = 64;
+
 #[repr(C)]
 #[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct AirdropPool {
@@ -13,15 +27,164 @@ pub struct AirdropPool {
     pub reward_per_account: u64,
     pub reward_per_referral: u64,
     pub max_referral_depth: u8,
+    pub authority: Pubkey,
+    pub claim_start_unix_timestamp: Option<UnixTimestamp>,
+    pub claim_end_unix_timestamp: Option<UnixTimestamp>,
+    pub paused: u8,
+    pub merkle_root: Option<[u8; 32]>,
+    pub voucher_signer: Option<Pubkey>,
+    pub vesting_duration_seconds: Option<u64>,
+    pub vesting_cliff_seconds: Option<u64>,
+    // Reward of the referrer at each depth, [reward_per_referral] is paid at every depth when not set
+    pub referral_rewards: Option<[u64; MAX_REFERRAL_DEPTH]>,
+    // Sum of the Deposit amounts, raw transfers to the pool token account are not counted
+    pub total_deposited: u64,
+    // Statistics, referral rewards are counted when credited to the referrer
+    pub total_claimers: u64,
+    pub total_base_rewards_paid: u64,
+    pub total_referral_rewards_paid: u64,
+    // Claimers must hold at least [gate_min_amount] tokens of [gate_mint] when set
+    pub gate_mint: Option<Pubkey>,
+    pub gate_min_amount: u64,
+    // Claimers pay [claim_fee_lamports] to [fee_recipient] when set
+    pub claim_fee_lamports: u64,
+    pub fee_recipient: Pubkey,
+    // Mints paid along with [token_mint_id] on each claim, from their own pool token account
+    pub reward_mints: [Option<RewardMint>; MAX_REWARD_MINTS],
+    // Rewards credited to claimers and still held by the pool token account, Withdraw and ClosePool can't take them
+    pub reserved_amount: u64,
+}
+
+impl AirdropPool {
+    // Pool tokens which are not reserved for the claimers
+    pub fn get_available_amount(&self, pool_token_amount: u64) -> u64 {
+        pool_token_amount.saturating_sub(self.reserved_amount)
+    }
+
+    // Claims take the fee recipient account when the pool charges a claim fee
+    pub fn get_fee_recipient(&self) -> Option<Pubkey> {
+        if self.claim_fee_lamports > 0 {
+            Some(self.fee_recipient)
+        } else {
+            None
+        }
+    }
+
+    // Reward mints with their slot, the slot of a reward mint never changes
+    pub fn get_reward_mints(&self) -> Vec<(usize, RewardMint)> {
+        self.reward_mints.iter()
+            .enumerate()
+            .filter_map(|(slot, reward_mint)| reward_mint.map(|reward_mint| (slot, reward_mint)))
+            .collect()
+    }
+
+    // [depth] starts at 1 for the direct referrer, which is also the bonus of the referred claimer
+    pub fn get_referral_reward(&self, depth: u8) -> u64 {
+        match self.referral_rewards {
+            None => self.reward_per_referral,
+            Some(referral_rewards) => {
+                referral_rewards.get(depth as usize - 1).cloned().unwrap_or(0)
+            }
+        }
+    }
+}
+
+implement_versioned_packable!(AirdropPool, AIRDROP_POOL_V1, 578 + AIRDROP_POOL_RESERVED_SIZE, [AirdropPoolV0]);
+
+// Reward of a mint paid along with the pool mint, [reward_per_referral] is paid at every depth
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct RewardMint {
+    pub token_mint_id: Pubkey,
+    pub reward_per_account: u64,
+    pub reward_per_referral: u64,
+    // Pending referral rewards of this mint with the transfer fee of their payout, held by its pool reward token account
+    pub reserved_amount: u64,
 }
 
-implement_packable!(AirdropPool, 85);
+impl RewardMint {
+    // Tokens of the pool reward token account which are not reserved for the referrers
+    pub fn get_available_amount(&self, pool_token_amount: u64) -> u64 {
+        pool_token_amount.saturating_sub(self.reserved_amount)
+    }
+}
+
+// Layout of the pools created before the authority, see MigratePool
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct AirdropPoolV0 {
+    pub token_program_id: Pubkey,
+    pub token_mint_id: Pubkey,
+    pub account_nonce: [u8; 4],
+    pub reward_per_account: u64,
+    pub reward_per_referral: u64,
+    pub max_referral_depth: u8,
+}
+
+implement_packable!(AirdropPoolV0, 85);
+
+impl From<AirdropPoolV0> for AirdropPool {
+    // The authority is set by MigratePool from the upgrade authority of the program
+    fn from(pool: AirdropPoolV0) -> Self {
+        AirdropPool {
+            token_program_id: pool.token_program_id,
+            token_mint_id: pool.token_mint_id,
+            account_nonce: pool.account_nonce,
+            reward_per_account: pool.reward_per_account,
+            reward_per_referral: pool.reward_per_referral,
+            max_referral_depth: pool.max_referral_depth,
+            ..AirdropPool::default()
+        }
+    }
+}
 
 #[repr(C)]
 #[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct AirdropClaimer {
     pub referrer_wallet: Option<Pubkey>,
     pub claimed: u8,
+    // Rewards of vesting pools are kept in the pool token account until released
+    pub vesting_start_unix_timestamp: UnixTimestamp,
+    pub vesting_amount: u64,
+    pub released_amount: u64,
+    // Referral rewards of pools without vesting, transferred with WithdrawReferralRewards
+    pub pending_referral_rewards: u64,
+    // Statistics of the claimer as a referrer
+    pub direct_referrals: u64,
+    pub indirect_referrals: u64,
+    pub referral_rewards_earned: u64,
+    // Referral rewards of the reward mints of the pool, by slot
+    pub pending_reward_mint_referral_rewards: [u64; MAX_REWARD_MINTS],
+}
+
+implement_versioned_packable!(AirdropClaimer, AIRDROP_CLAIMER_V1, 115 + AIRDROP_CLAIMER_RESERVED_SIZE, [AirdropClaimerV0]);
+
+// Layout of the claimers created before vesting and referral rewards, see MigratePool
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct AirdropClaimerV0 {
+    pub referrer_wallet: Option<Pubkey>,
+    pub claimed: u8,
+}
+
+implement_packable!(AirdropClaimerV0, 34);
+
+impl From<AirdropClaimerV0> for AirdropClaimer {
+    fn from(claimer: AirdropClaimerV0) -> Self {
+        AirdropClaimer {
+            referrer_wallet: claimer.referrer_wallet,
+            claimed: claimer.claimed,
+            ..AirdropClaimer::default()
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct AirdropDepositor {
+    pub pool_account: Pubkey,
+    pub depositor_wallet: Pubkey,
+    pub total_deposited: u64,
 }
 
-implement_packable!(AirdropClaimer, 34);
\ No newline at end of file
+implement_versioned_packable!(AirdropDepositor, AIRDROP_DEPOSITOR_V1, 73, []);
\ No newline at end of file
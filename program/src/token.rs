@@ -0,0 +1,152 @@
+//! Token program helpers
+//!
+//! Pools hold either SPL Token or Token-2022 mints, the token program of a pool is the one it was
+//! initialized with. Token-2022 mints may charge a transfer fee, payouts are grossed up so that
+//! claimers receive their full reward.
+//!
+//! Native SOL pools are initialized with the system program and the native mint, they pay lamports
+//! from the pool account itself, and the token account of a wallet is the wallet.
+
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::Sysvar;
+use spl_token_2022::extension::{ExtensionType, StateWithExtensions};
+use spl_token_2022::extension::transfer_fee::{TransferFee, TransferFeeConfig, MAX_FEE_BASIS_POINTS};
+use spl_token_2022::state::{Account, Mint};
+
+pub fn is_token_program(token_program_id: &Pubkey) -> bool {
+    token_program_id == &spl_token::id() || token_program_id == &spl_token_2022::id()
+}
+
+pub fn is_native(token_program_id: &Pubkey) -> bool {
+    token_program_id == &system_program::id()
+}
+
+// Base state of a token account of either token program, without its extensions
+pub fn unpack_account(token_account: &AccountInfo) -> Result<Account, ProgramError> {
+    let data = token_account.data.borrow();
+    let token_account_state = StateWithExtensions::<Account>::unpack(&data)?;
+    Ok(token_account_state.base)
+}
+
+pub fn get_amount(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+    Ok(unpack_account(token_account)?.amount)
+}
+
+// Lamports of a native SOL pool, the pool account keeps its rent exemption
+pub fn get_native_amount(pool_account: &AccountInfo) -> Result<u64, ProgramError> {
+    let rent = Rent::get()?;
+    Ok(pool_account.lamports().saturating_sub(rent.minimum_balance(pool_account.data_len())))
+}
+
+// Rewards left in the pool, in its pool token account or in the pool account of native SOL pools
+pub fn get_pool_amount(token_program: &AccountInfo,
+                       pool_account: &AccountInfo,
+                       pool_token_account: &AccountInfo) -> Result<u64, ProgramError> {
+    if is_native(token_program.key) {
+        return get_native_amount(pool_account);
+    }
+    get_amount(pool_token_account)
+}
+
+pub fn get_decimals(token_mint: &AccountInfo) -> Result<u8, ProgramError> {
+    let data = token_mint.data.borrow();
+    let token_mint_state = StateWithExtensions::<Mint>::unpack(&data)?;
+    Ok(token_mint_state.base.decimals)
+}
+
+// SPL Token pools keep the unchecked transfer, which doesn't read the mint
+pub fn transfer_instruction(token_program: &AccountInfo,
+                            source: &Pubkey,
+                            token_mint: &AccountInfo,
+                            destination: &Pubkey,
+                            authority: &Pubkey,
+                            signer_pubkeys: &[&Pubkey],
+                            amount: u64) -> Result<Instruction, ProgramError> {
+    if token_program.key == &spl_token::id() {
+        return spl_token::instruction::transfer(
+            token_program.key,
+            source,
+            destination,
+            authority,
+            signer_pubkeys,
+            amount,
+        );
+    }
+    spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        source,
+        token_mint.key,
+        destination,
+        authority,
+        signer_pubkeys,
+        amount,
+        get_decimals(token_mint)?,
+    )
+}
+
+// Size of a new token account of [token_mint], Token-2022 mints may require account extensions
+pub fn get_account_len(token_program: &AccountInfo, token_mint: &AccountInfo) -> Result<usize, ProgramError> {
+    if token_program.key == &spl_token::id() {
+        return Ok(spl_token::state::Account::LEN);
+    }
+    let data = token_mint.data.borrow();
+    let token_mint_state = StateWithExtensions::<Mint>::unpack(&data)?;
+    let account_extensions = ExtensionType::get_required_init_account_extensions(&token_mint_state.get_extension_types()?);
+    Ok(ExtensionType::get_account_len::<Account>(&account_extensions))
+}
+
+// Transfer fee of the current epoch, SPL Token mints and native SOL don't have any
+pub fn get_transfer_fee(token_program: &AccountInfo, token_mint: &AccountInfo) -> Result<Option<TransferFee>, ProgramError> {
+    if token_program.key == &spl_token::id() || is_native(token_program.key) {
+        return Ok(None);
+    }
+    let data = token_mint.data.borrow();
+    let token_mint_state = StateWithExtensions::<Mint>::unpack(&data)?;
+    let transfer_fee_config = match token_mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config,
+        Err(_) => return Ok(None),
+    };
+    let epoch = Clock::get()?.epoch;
+    Ok(Some(*transfer_fee_config.get_epoch_fee(epoch)))
+}
+
+// Amount to send so that [net_amount] reaches the destination once the transfer fee is withheld
+pub fn get_gross_amount(transfer_fee: Option<&TransferFee>, net_amount: u64) -> Option<u64> {
+    let transfer_fee = match transfer_fee {
+        Some(transfer_fee) => transfer_fee,
+        None => return Some(net_amount),
+    };
+    let basis_points = u16::from(transfer_fee.transfer_fee_basis_points) as u128;
+    let maximum_fee = u64::from(transfer_fee.maximum_fee);
+    if basis_points == 0 || net_amount == 0 {
+        return Some(net_amount);
+    }
+
+    let capped_amount = net_amount.checked_add(maximum_fee)?;
+    let max_basis_points = MAX_FEE_BASIS_POINTS as u128;
+    if basis_points >= max_basis_points {
+        return Some(capped_amount);
+    }
+
+    // The fee is rounded up, so (gross - fee) >= net for the smallest gross with gross * (max - bps) >= net * max
+    let numerator = (net_amount as u128).checked_mul(max_basis_points)?;
+    let denominator = max_basis_points - basis_points;
+    let gross_amount = numerator.checked_add(denominator - 1)? / denominator;
+    if gross_amount >= capped_amount as u128 {
+        return Some(capped_amount);
+    }
+    Some(gross_amount as u64)
+}
+
+// Increase of the gross amount when an amount owed grows from [previous_net_amount] to [net_amount], reserving
+// the increase keeps a reservation exact however the amount owed is credited and paid out
+pub fn get_gross_amount_increase(transfer_fee: Option<&TransferFee>, previous_net_amount: u64, net_amount: u64) -> Option<u64> {
+    get_gross_amount(transfer_fee, net_amount)?.checked_sub(get_gross_amount(transfer_fee, previous_net_amount)?)
+}
@@ -0,0 +1,39 @@
+//! Linear vesting of claimed rewards
+//!
+//! The rewards of a claimer (including the referral rewards it earns afterwards) vest linearly
+//! over [vesting_duration_seconds], starting when it claims. Nothing vests before the optional cliff.
+
+use solana_program::clock::UnixTimestamp;
+
+use crate::state::{AirdropClaimer, AirdropPool};
+
+pub fn get_vested_amount(pool_account_state: &AirdropPool,
+                         claimer_account_state: &AirdropClaimer,
+                         unix_timestamp: UnixTimestamp) -> u64 {
+    let total = claimer_account_state.vesting_amount;
+    let duration = match pool_account_state.vesting_duration_seconds {
+        Some(duration) if duration > 0 => duration,
+        _ => return total,
+    };
+
+    let elapsed = unix_timestamp.saturating_sub(claimer_account_state.vesting_start_unix_timestamp);
+    if elapsed <= 0 {
+        return 0;
+    }
+    let elapsed = elapsed as u64;
+    if elapsed < pool_account_state.vesting_cliff_seconds.unwrap_or(0) {
+        return 0;
+    }
+    if elapsed >= duration {
+        return total;
+    }
+
+    (total as u128 * elapsed as u128 / duration as u128) as u64
+}
+
+pub fn get_releasable_amount(pool_account_state: &AirdropPool,
+                             claimer_account_state: &AirdropClaimer,
+                             unix_timestamp: UnixTimestamp) -> u64 {
+    get_vested_amount(pool_account_state, claimer_account_state, unix_timestamp)
+        .saturating_sub(claimer_account_state.released_amount)
+}
@@ -0,0 +1,113 @@
+//! Vouchers of sybil resistant pools
+//!
+//! A voucher is a message "[claimer_wallet] may claim in [pool_account] before [expiry_slot]",
+//! signed off-chain by the pool voucher signer. It is checked by the ed25519 native program
+//! in the instruction preceding the claim, which the program then reads from the instructions sysvar.
+
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Slot;
+use solana_program::ed25519_program;
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+
+use crate::error::AirdropPoolError;
+
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+const PUBLIC_KEY_OFFSET: usize = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE;
+const SIGNATURE_OFFSET: usize = PUBLIC_KEY_OFFSET + 32;
+const MESSAGE_OFFSET: usize = SIGNATURE_OFFSET + 64;
+pub const MESSAGE_SIZE: usize = 32 + 32 + 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Voucher {
+    pub expiry_slot: Slot,
+    pub signature: [u8; 64],
+}
+
+pub fn get_message(pool_account: &Pubkey, claimer_wallet: &Pubkey, expiry_slot: Slot) -> Vec<u8> {
+    let mut result = Vec::with_capacity(MESSAGE_SIZE);
+    result.extend_from_slice(pool_account.as_ref());
+    result.extend_from_slice(claimer_wallet.as_ref());
+    result.extend_from_slice(&expiry_slot.to_le_bytes());
+    result
+}
+
+// Ed25519 program instruction verifying a single signature, with everything in its own data
+pub fn new_ed25519_instruction(voucher_signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Instruction {
+    let mut data = Vec::with_capacity(MESSAGE_OFFSET + message.len());
+    data.push(1); // Number of signatures
+    data.push(0); // Padding
+    data.extend_from_slice(&(SIGNATURE_OFFSET as u16).to_le_bytes());
+    data.extend_from_slice(&u16::MAX.to_le_bytes());
+    data.extend_from_slice(&(PUBLIC_KEY_OFFSET as u16).to_le_bytes());
+    data.extend_from_slice(&u16::MAX.to_le_bytes());
+    data.extend_from_slice(&(MESSAGE_OFFSET as u16).to_le_bytes());
+    data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+    data.extend_from_slice(&u16::MAX.to_le_bytes());
+    data.extend_from_slice(voucher_signer.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+
+    Instruction::new_with_bytes(ed25519_program::id(), &data, vec![])
+}
+
+pub fn verify(
+    instructions_sysvar: &AccountInfo,
+    voucher_signer: &Pubkey,
+    pool_account: &Pubkey,
+    claimer_wallet: &Pubkey,
+    current_slot: Slot,
+) -> Result<(), ProgramError> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    if current_index == 0 {
+        return Err(AirdropPoolError::VoucherRequired.into());
+    }
+    let instruction = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    if instruction.program_id != ed25519_program::id() {
+        return Err(AirdropPoolError::VoucherRequired.into());
+    }
+
+    let (public_key, message) = get_signed_data(&instruction.data)
+        .ok_or(AirdropPoolError::InvalidVoucher)?;
+    if public_key != voucher_signer.as_ref() {
+        return Err(AirdropPoolError::InvalidVoucher.into());
+    }
+    if message.len() != MESSAGE_SIZE
+        || &message[..32] != pool_account.as_ref()
+        || &message[32..64] != claimer_wallet.as_ref() {
+        return Err(AirdropPoolError::InvalidVoucher.into());
+    }
+
+    let mut expiry_slot = [0; 8];
+    expiry_slot.copy_from_slice(&message[64..]);
+    if current_slot >= Slot::from_le_bytes(expiry_slot) {
+        return Err(AirdropPoolError::VoucherExpired.into());
+    }
+
+    Ok(())
+}
+
+// Returns the (public key, message) of an ed25519 program instruction verifying a single signature,
+// provided they are stored in the instruction itself.
+fn get_signed_data(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    if data.len() < PUBLIC_KEY_OFFSET || data[0] != 1 {
+        return None;
+    }
+    let read_u16 = |index: usize| {
+        let offset = SIGNATURE_OFFSETS_START + 2 * index;
+        u16::from_le_bytes([data[offset], data[offset + 1]])
+    };
+    let public_key_offset = read_u16(2) as usize;
+    let message_offset = read_u16(4) as usize;
+    let message_size = read_u16(5) as usize;
+    if read_u16(1) != u16::MAX || read_u16(3) != u16::MAX || read_u16(6) != u16::MAX {
+        return None;
+    }
+
+    let public_key = data.get(public_key_offset..public_key_offset + 32)?;
+    let message = data.get(message_offset..message_offset + message_size)?;
+    Some((public_key, message))
+}
\ No newline at end of file
@@ -0,0 +1,240 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::{AirdropClaimer, AirdropPool},
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token,
+};
+
+use chikin_airdrop_pool::instruction::{AirdropPoolInstruction, DepositAccountKeys, WithdrawAccountKeys};
+use chikin_airdrop_pool::packable::Packable;
+use testutils::{ClaimBuilder, InitializeBuilder, ProgramInfo, UserInfo};
+use testutils::{add_token_account, assert_error, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_account_validation() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let other_token_mint_id = Pubkey::new_unique();
+    let authority = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let pool_state = AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [1, 0, 1, 0],
+        reward_per_account: 500,
+        reward_per_referral: 100,
+        max_referral_depth: 1,
+        authority: authority.pubkey(),
+        ..AirdropPool::default()
+    };
+    let program_info = ProgramInfo::create_with_state(&mut program_test, &program_id, pool_state.clone());
+    let pool_account_id = program_info.pool_account_id;
+
+    // Pool of a mint which is not owned by the token program
+    let fake_token_mint_id = Pubkey::new_unique();
+    let fake_mint_program_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_mint_id: fake_token_mint_id,
+        ..pool_state.clone()
+    });
+    program_test.add_account(
+        fake_token_mint_id,
+        Account {
+            lamports: 1_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let user_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let fake_mint_user_info = UserInfo::create(&mut program_test, program_id, fake_token_mint_id, fake_mint_program_info.pool_account_id);
+
+    // Claimers whose token account has another mint or another owner
+    let wrong_mint_user_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    add_token_account(&mut program_test, wrong_mint_user_info.token_account, other_token_mint_id, wrong_mint_user_info.wallet.pubkey(), 0);
+    let wrong_owner_user_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    add_token_account(&mut program_test, wrong_owner_user_info.token_account, token_mint_id, Pubkey::new_unique(), 0);
+
+    // Referrers with pending rewards whose token account has another mint or another owner
+    let wrong_mint_referrer_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    add_referrer_account(&mut program_test, program_id, &wrong_mint_referrer_info);
+    add_token_account(&mut program_test, wrong_mint_referrer_info.token_account, other_token_mint_id, wrong_mint_referrer_info.wallet.pubkey(), 0);
+    let wrong_owner_referrer_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    add_referrer_account(&mut program_test, program_id, &wrong_owner_referrer_info);
+    add_token_account(&mut program_test, wrong_owner_referrer_info.token_account, token_mint_id, Pubkey::new_unique(), 0);
+
+    // Token account of another mint
+    let other_token_account_id = Pubkey::new_unique();
+    add_token_account(&mut program_test, other_token_account_id, other_token_mint_id, user_info.wallet.pubkey(), 0);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Claim
+
+    let mut instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &user_info, &[]);
+    instruction.accounts[5].pubkey = Pubkey::new_unique();
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::SystemProgramKeyMismatch);
+
+    let mut instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &user_info, &[]);
+    instruction.accounts[6].pubkey = spl_token_2022::id();
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::TokenProgramKeyMismatch);
+
+    let instruction = claim_instruction(program_id, fake_token_mint_id, fake_mint_program_info.pool_account_id, &fake_mint_user_info, &[]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&fake_mint_user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::TokenMintOwnerMismatch);
+
+    let instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &wrong_mint_user_info, &[]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&wrong_mint_user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::UserTokenAccountMintMismatch);
+
+    let instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &wrong_owner_user_info, &[]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&wrong_owner_user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::UserTokenAccountOwnerMismatch);
+
+    let mut instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &user_info, &[]);
+    instruction.accounts[0].pubkey = payer.pubkey();
+    instruction.accounts[11].is_signer = false;
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::ClaimerDidNotSign);
+
+    let mut instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &user_info, &[]);
+    instruction.accounts[12].is_writable = false;
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::AccountNotWritable);
+
+    // The claimer account of a referrer which never claimed doesn't exist
+    let referrer_wallet_id = Pubkey::new_unique();
+    let instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &user_info, &[referrer_wallet_id]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::UserAccountOwnerMismatch);
+
+    // WithdrawReferralRewards
+
+    let instruction = withdraw_referral_rewards_instruction(program_id, token_mint_id, pool_account_id, &wrong_mint_referrer_info);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::ReferrerTokenAccountMintMismatch);
+
+    let instruction = withdraw_referral_rewards_instruction(program_id, token_mint_id, pool_account_id, &wrong_owner_referrer_info);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::ReferrerTokenAccountOwnerMismatch);
+
+    // Deposit
+
+    let deposit_keys = DepositAccountKeys {
+        depositor_wallet: user_info.wallet.pubkey(),
+        program: program_id,
+        rent_sysvar: solana_program::sysvar::rent::id(),
+        system_program: solana_program::system_program::id(),
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        depositor_token_account: other_token_account_id,
+    };
+    let instruction = AirdropPoolInstruction::deposit(&deposit_keys, 100);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::DepositorTokenAccountMintMismatch);
+
+    let instruction = AirdropPoolInstruction::deposit(
+        &DepositAccountKeys {
+            depositor_token_account: user_info.wallet.pubkey(),
+            ..deposit_keys
+        },
+        100,
+    );
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::DepositorTokenAccountOwnerMismatch);
+
+    // Withdraw
+
+    let instruction = AirdropPoolInstruction::withdraw(&WithdrawAccountKeys {
+        authority: authority.pubkey(),
+        program: program_id,
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        destination_token_account: other_token_account_id,
+        reward_mint_list: vec![],
+    }, 100);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+    assert_error(result, AirdropPoolError::DestinationTokenAccountMintMismatch);
+
+    // Initialize
+
+    let mut instruction = InitializeBuilder {
+        reward_per_account: 500,
+        reward_per_referral: 100,
+        max_referral_depth: 1,
+        ..InitializeBuilder::new(program_id, token_mint_id, [2, 0, 1, 0])
+    }.build(Pubkey::new_unique());
+    instruction.accounts[0].is_signer = false;
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::FunderDidNotSign);
+}
+
+fn add_referrer_account(program_test: &mut ProgramTest, program_id: Pubkey, user_info: &UserInfo) {
+    let account_state = AirdropClaimer {
+        claimed: 1,
+        pending_referral_rewards: 100,
+        ..AirdropClaimer::default()
+    };
+    program_test.add_account(
+        user_info.account,
+        Account {
+            lamports: 5,
+            data: account_state.pack(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+}
+
+fn claim_instruction(program_id: Pubkey,
+                     token_mint_id: Pubkey,
+                     pool_account_id: Pubkey,
+                     user_info: &UserInfo,
+                     referrer_wallet_list: &[Pubkey]) -> Instruction {
+    ClaimBuilder::new(program_id, token_mint_id, pool_account_id)
+        .referrers(referrer_wallet_list)
+        .build(user_info.wallet.pubkey())
+}
+
+fn withdraw_referral_rewards_instruction(program_id: Pubkey,
+                                         token_mint_id: Pubkey,
+                                         pool_account_id: Pubkey,
+                                         user_info: &UserInfo) -> Instruction {
+    AirdropPoolInstruction::withdraw_referral_rewards(
+        program_id,
+        spl_token::id(),
+        token_mint_id,
+        pool_account_id,
+        user_info.wallet.pubkey(),
+        &[],
+    )
+}
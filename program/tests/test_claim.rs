@@ -12,14 +12,14 @@ use {
         program_pack::Pack,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
-        transaction::Transaction,
     },
     spl_token::{self, state::Account as SplTokenAccount},
 };
 
-use chikin_airdrop_pool::instruction::AirdropPoolInstruction;
+use testutils::ClaimBuilder;
 use testutils::ProgramInfo;
 use testutils::UserInfo;
+use testutils::send_instruction;
 use chikin_airdrop_pool::packable::Packable;
 
 mod testutils;
@@ -59,7 +59,8 @@ async fn test_claim() {
                                            pool_account_nonce,
                                            reward_per_account,
                                            reward_per_referral,
-                                           max_referral_depth);
+                                           max_referral_depth,
+                                           Pubkey::new_unique());
 
     let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, program_info.pool_account_id);
     let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, program_info.pool_account_id);
@@ -160,23 +161,10 @@ async fn claim_reward(banks_client: &mut BanksClient,
                       pool_account_id: Pubkey,
                       user_info: &UserInfo,
                       referrers: &[&UserInfo]) {
-    let instruction = AirdropPoolInstruction::claim(
-        program_id,
-        solana_program::sysvar::rent::id(),
-        solana_program::system_program::id(),
-        spl_token::id(),
-        token_mint_id,
-        pool_account_id,
-        user_info.wallet.pubkey(),
-        &referrers.iter().map(|e| e.wallet.pubkey()).collect::<Vec<Pubkey>>(),
-    );
-
-    let mut transaction = Transaction::new_with_payer(
-        &[instruction],
-        Some(&payer.pubkey()),
-    );
-    transaction.sign(&[payer, &user_info.wallet], recent_blockhash);
-    banks_client.process_transaction(transaction).await.unwrap();
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, pool_account_id)
+        .referrers(&referrers.iter().map(|e| e.wallet.pubkey()).collect::<Vec<Pubkey>>())
+        .build(user_info.wallet.pubkey());
+    send_instruction(banks_client, payer, recent_blockhash, instruction, &[&user_info.wallet]).await.unwrap();
 }
 
 // TODO REMOVE
@@ -0,0 +1,83 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        config,
+        processor::process_instruction,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token::{self, state::Account as SplTokenAccount},
+};
+
+use testutils::ClaimBuilder;
+use testutils::ProgramInfo;
+use testutils::send_instruction;
+
+mod testutils;
+
+#[tokio::test]
+async fn test_claim_create_token_account() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    program_test.add_program(
+        "AssociatedTokenProgram",
+        spl_associated_token_account::id(),
+        processor!(spl_associated_token_account::processor::process_instruction),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           2,
+                                           Pubkey::new_unique());
+
+    // Claimer without a token account
+    let claimer_wallet = Keypair::new();
+    program_test.add_account(
+        claimer_wallet.pubkey(),
+        Account {
+            lamports: 10_000_000,
+            ..Account::default()
+        },
+    );
+    let claimer_token_account = config::get_claimer_token_account(&token_program_id, &token_mint_id, &claimer_wallet.pubkey());
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, program_info.pool_account_id)
+        .build(claimer_wallet.pubkey());
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&claimer_wallet]).await.unwrap();
+
+    let token_account = banks_client.get_account(claimer_token_account)
+        .await
+        .expect("claimer_token_account get_account failed")
+        .expect("claimer_token_account not found");
+    let token_account_state = SplTokenAccount::unpack(&token_account.data).unwrap();
+    assert_eq!(token_account_state.owner, claimer_wallet.pubkey());
+    assert_eq!(token_account_state.amount, 500);
+}
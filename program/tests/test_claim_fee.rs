@@ -0,0 +1,100 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::AirdropPool,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        pubkey::Pubkey,
+        signature::Signer,
+    },
+    spl_token,
+};
+
+use testutils::{ClaimBuilder, InitializeBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_claim_fee() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let fee_recipient_id = Pubkey::new_unique();
+    let claim_fee_lamports = 1_000_000;
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [1, 0, 1, 0],
+        reward_per_account: 500,
+        claim_fee_lamports,
+        fee_recipient: fee_recipient_id,
+        ..AirdropPool::default()
+    });
+    let pool_account_id = program_info.pool_account_id;
+
+    program_test.add_account(
+        fee_recipient_id,
+        Account {
+            lamports: 1_000_000,
+            ..Account::default()
+        },
+    );
+
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // The fee recipient must be the one of the pool
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, pool_account_id)
+        .fee_recipient(Pubkey::new_unique())
+        .build(user1_info.wallet.pubkey());
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user1_info.wallet]).await;
+    assert_error(result, AirdropPoolError::FeeRecipientKeyMismatch);
+
+    // The claimer wallet pays the fee
+    let user2_balance = banks_client.get_balance(user2_info.wallet.pubkey()).await.unwrap();
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, pool_account_id)
+        .fee_recipient(fee_recipient_id)
+        .build(user2_info.wallet.pubkey());
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user2_info.wallet]).await.unwrap();
+
+    assert_eq!(banks_client.get_balance(fee_recipient_id).await.unwrap(), 1_000_000 + claim_fee_lamports);
+    let claimer_account = banks_client.get_account(user2_info.account)
+        .await
+        .expect("claimer_account get_account failed")
+        .expect("claimer_account not found");
+    assert_eq!(
+        banks_client.get_balance(user2_info.wallet.pubkey()).await.unwrap(),
+        user2_balance - claim_fee_lamports - claimer_account.lamports,
+    );
+
+    // A pool charging a fee needs a fee recipient
+    let instruction = InitializeBuilder {
+        reward_per_account: 500,
+        claim_fee_lamports,
+        ..InitializeBuilder::new(program_id, token_mint_id, [2, 0, 1, 0])
+    }.build(payer.pubkey());
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::InvalidClaimFee);
+}
@@ -0,0 +1,77 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::AirdropPool,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        pubkey::Pubkey,
+    },
+};
+
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, send_claim};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_claim_window() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let pool_state = AirdropPool {
+        token_program_id,
+        token_mint_id,
+        reward_per_account: 500,
+        reward_per_referral: 100,
+        max_referral_depth: 2,
+        ..AirdropPool::default()
+    };
+    let not_started_pool_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        account_nonce: [1, 0, 0, 0],
+        claim_start_unix_timestamp: Some(i64::MAX),
+        ..pool_state.clone()
+    });
+    let ended_pool_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        account_nonce: [2, 0, 0, 0],
+        claim_end_unix_timestamp: Some(0),
+        ..pool_state.clone()
+    });
+    let open_pool_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        account_nonce: [3, 0, 0, 0],
+        claim_start_unix_timestamp: Some(0),
+        claim_end_unix_timestamp: Some(i64::MAX),
+        ..pool_state.clone()
+    });
+
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, not_started_pool_info.pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, ended_pool_info.pool_account_id);
+    let user3_info = UserInfo::create(&mut program_test, program_id, token_mint_id, open_pool_info.pool_account_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &ClaimBuilder::new(program_id, token_mint_id, not_started_pool_info.pool_account_id), &user1_info).await;
+    assert_error(result, AirdropPoolError::ClaimNotStarted);
+
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &ClaimBuilder::new(program_id, token_mint_id, ended_pool_info.pool_account_id), &user2_info).await;
+    assert_error(result, AirdropPoolError::ClaimEnded);
+
+    send_claim(&mut banks_client, &payer, recent_blockhash, &ClaimBuilder::new(program_id, token_mint_id, open_pool_info.pool_account_id), &user3_info).await.unwrap();
+}
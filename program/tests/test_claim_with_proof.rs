@@ -0,0 +1,92 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        merkle,
+        processor::process_instruction,
+        state::AirdropPool,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::Signer,
+    },
+    spl_token,
+};
+
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, get_token_amount, send_claim, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_claim_with_proof() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let pool_account_id = chikin_airdrop_pool::config::get_pool_account(&program_id, &token_mint_id, &[1, 0, 1, 0]).0;
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user3_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user4_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+
+    let allowlist = vec![
+        (user1_info.wallet.pubkey(), 100),
+        (user2_info.wallet.pubkey(), 200),
+        (user3_info.wallet.pubkey(), 300),
+    ];
+    let leaves: Vec<[u8; 32]> = allowlist.iter()
+        .map(|(wallet, amount)| merkle::get_leaf(wallet, *amount))
+        .collect();
+
+    ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [1, 0, 1, 0],
+        reward_per_account: 500,
+        merkle_root: Some(merkle::get_root(&leaves)),
+        ..AirdropPool::default()
+    });
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, pool_account_id);
+
+    // Allowlisted wallets claim their own amount
+    let instruction = claim.build_with_proof(user2_info.wallet.pubkey(), 200, merkle::get_proof(&leaves, 1));
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user2_info.wallet]).await.unwrap();
+    assert_eq!(get_token_amount(&mut banks_client, user2_info.token_account).await, 200);
+
+    let instruction = claim.build_with_proof(user3_info.wallet.pubkey(), 300, merkle::get_proof(&leaves, 2));
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user3_info.wallet]).await.unwrap();
+    assert_eq!(get_token_amount(&mut banks_client, user3_info.token_account).await, 300);
+
+    // The amount is part of the leaf
+    let instruction = claim.build_with_proof(user1_info.wallet.pubkey(), 1000, merkle::get_proof(&leaves, 0));
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user1_info.wallet]).await;
+    assert_error(result, AirdropPoolError::InvalidMerkleProof);
+
+    // Wallets outside of the allowlist can't claim
+    let instruction = claim.build_with_proof(user4_info.wallet.pubkey(), 100, merkle::get_proof(&leaves, 0));
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user4_info.wallet]).await;
+    assert_error(result, AirdropPoolError::InvalidMerkleProof);
+
+    // Regular claims are rejected by allowlist pools
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim, &user1_info).await;
+    assert_error(result, AirdropPoolError::MerkleProofRequired);
+}
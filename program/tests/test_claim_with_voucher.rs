@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::AirdropPool,
+        voucher,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        clock::Slot,
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+};
+
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_instruction_error, send_instructions};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_claim_with_voucher() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let voucher_signer = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [1, 0, 1, 0],
+        reward_per_account: 500,
+        voucher_signer: Some(voucher_signer.pubkey()),
+        ..AirdropPool::default()
+    });
+    let pool_account_id = program_info.pool_account_id;
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user3_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user4_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, pool_account_id);
+
+    // Claims without a voucher are rejected
+    let instructions = vec![claim.build(user1_info.wallet.pubkey())];
+    let result = send_instructions(&mut banks_client, &payer, recent_blockhash, &instructions, &[&user1_info.wallet]).await;
+    assert_instruction_error(result, 0, AirdropPoolError::VoucherRequired);
+
+    // Vouchers are bound to the claimer wallet
+    let instructions = vec![
+        voucher_instruction(&voucher_signer, pool_account_id, &user3_info, Slot::MAX),
+        claim.build(user2_info.wallet.pubkey()),
+    ];
+    let result = send_instructions(&mut banks_client, &payer, recent_blockhash, &instructions, &[&user2_info.wallet]).await;
+    assert_instruction_error(result, 1, AirdropPoolError::InvalidVoucher);
+
+    // Vouchers expire
+    let instructions = vec![
+        voucher_instruction(&voucher_signer, pool_account_id, &user3_info, 0),
+        claim.build(user3_info.wallet.pubkey()),
+    ];
+    let result = send_instructions(&mut banks_client, &payer, recent_blockhash, &instructions, &[&user3_info.wallet]).await;
+    assert_instruction_error(result, 1, AirdropPoolError::VoucherExpired);
+
+    // Valid voucher
+    let instructions = vec![
+        voucher_instruction(&voucher_signer, pool_account_id, &user4_info, Slot::MAX),
+        claim.build(user4_info.wallet.pubkey()),
+    ];
+    send_instructions(&mut banks_client, &payer, recent_blockhash, &instructions, &[&user4_info.wallet]).await.unwrap();
+}
+
+fn voucher_instruction(voucher_signer: &Keypair,
+                       pool_account_id: Pubkey,
+                       user_info: &UserInfo,
+                       expiry_slot: Slot) -> Instruction {
+    let message = voucher::get_message(&pool_account_id, &user_info.wallet.pubkey(), expiry_slot);
+    let mut signature = [0; 64];
+    signature.copy_from_slice(voucher_signer.sign_message(&message).as_ref());
+    voucher::new_ed25519_instruction(&voucher_signer.pubkey(), &signature, &message)
+}
@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::{AirdropClaimer, AirdropPool},
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+};
+
+use chikin_airdrop_pool::instruction::{AirdropPoolInstruction, ClosePoolAccountKeys};
+use chikin_airdrop_pool::packable::Packable;
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, send_context_instruction, set_unix_timestamp};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_close_claimer() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let authority = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let pool_state = AirdropPool {
+        token_program_id,
+        token_mint_id,
+        reward_per_account: 500,
+        authority: authority.pubkey(),
+        ..AirdropPool::default()
+    };
+    let ending_pool_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        account_nonce: [1, 0, 0, 0],
+        claim_end_unix_timestamp: Some(1_000_000),
+        ..pool_state.clone()
+    });
+    let closing_pool_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        account_nonce: [2, 0, 0, 0],
+        ..pool_state.clone()
+    });
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, ending_pool_info.pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, closing_pool_info.pool_account_id);
+
+    // Referrer which still has to withdraw referral rewards of a reward mint
+    let user3_info = UserInfo::create(&mut program_test, program_id, token_mint_id, ending_pool_info.pool_account_id);
+    let user3_account_state = AirdropClaimer {
+        claimed: 1,
+        pending_reward_mint_referral_rewards: [0, 100, 0],
+        ..AirdropClaimer::default()
+    };
+    program_test.add_account(
+        user3_info.account,
+        Account {
+            lamports: 1_000_000_000,
+            data: user3_account_state.pack(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    let mut context = program_test.start_with_context().await;
+    set_unix_timestamp(&mut context, 2, Some(999_000)).await;
+
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, ending_pool_info.pool_account_id).build(user1_info.wallet.pubkey());
+    send_context_instruction(&mut context, instruction, &[&user1_info.wallet]).await.unwrap();
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, closing_pool_info.pool_account_id).build(user2_info.wallet.pubkey());
+    send_context_instruction(&mut context, instruction, &[&user2_info.wallet]).await.unwrap();
+
+    // Claimer accounts of active pools can't be closed
+    for (pool_info, user_info) in &[(&ending_pool_info, &user1_info), (&closing_pool_info, &user2_info)] {
+        let instruction = close_claimer_instruction(program_id, pool_info.pool_account_id, user_info);
+        let result = send_context_instruction(&mut context, instruction, &[&user_info.wallet]).await;
+        assert_error(result, AirdropPoolError::PoolStillActive);
+    }
+
+    // Closed pool
+    let instruction = AirdropPoolInstruction::close_pool(&ClosePoolAccountKeys {
+        authority: authority.pubkey(),
+        program: program_id,
+        token_program: spl_token::id(),
+        token_mint: token_mint_id,
+        pool_account: closing_pool_info.pool_account_id,
+        destination_token_account: user2_info.token_account,
+        destination_wallet: authority.pubkey(),
+        reward_mint_list: vec![],
+    });
+    send_context_instruction(&mut context, instruction, &[&authority]).await.unwrap();
+
+    set_unix_timestamp(&mut context, 4, Some(999_500)).await;
+    let instruction = close_claimer_instruction(program_id, closing_pool_info.pool_account_id, &user2_info);
+    send_context_instruction(&mut context, instruction, &[&user2_info.wallet]).await.unwrap();
+    assert!(context.banks_client.get_account(user2_info.account).await.unwrap().is_none());
+
+    // Ended pool
+    set_unix_timestamp(&mut context, 6, Some(1_000_000)).await;
+    let instruction = close_claimer_instruction(program_id, ending_pool_info.pool_account_id, &user3_info);
+    let result = send_context_instruction(&mut context, instruction, &[&user3_info.wallet]).await;
+    assert_error(result, AirdropPoolError::ClaimerHasPendingRewards);
+
+    let lamports_before = get_lamports(&mut context, user1_info.wallet.pubkey()).await;
+    let claimer_account_lamports = get_lamports(&mut context, user1_info.account).await;
+    let instruction = close_claimer_instruction(program_id, ending_pool_info.pool_account_id, &user1_info);
+    send_context_instruction(&mut context, instruction, &[&user1_info.wallet]).await.unwrap();
+    assert!(context.banks_client.get_account(user1_info.account).await.unwrap().is_none());
+    assert_eq!(get_lamports(&mut context, user1_info.wallet.pubkey()).await, lamports_before + claimer_account_lamports);
+}
+
+fn close_claimer_instruction(program_id: Pubkey,
+                             pool_account_id: Pubkey,
+                             user_info: &UserInfo) -> Instruction {
+    AirdropPoolInstruction::close_claimer(
+        program_id,
+        solana_program::sysvar::clock::id(),
+        pool_account_id,
+        user_info.wallet.pubkey(),
+    )
+}
+
+async fn get_lamports(context: &mut ProgramTestContext, account_id: Pubkey) -> u64 {
+    context.banks_client.get_account(account_id)
+        .await
+        .expect("get_account failed")
+        .expect("account not found")
+        .lamports
+}
@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        config,
+        processor::process_instruction,
+        state::AirdropDepositor,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token,
+};
+
+use chikin_airdrop_pool::instruction::{AirdropPoolInstruction, DepositAccountKeys};
+use chikin_airdrop_pool::packable::Packable;
+use testutils::ProgramInfo;
+use testutils::{add_token_account, get_pool_state, get_token_amount, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_deposit() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           2,
+                                           Pubkey::new_unique());
+
+    // Depositor holding 1000 tokens
+    let depositor_wallet = Keypair::new();
+    program_test.add_account(
+        depositor_wallet.pubkey(),
+        Account {
+            lamports: 10_000_000,
+            ..Account::default()
+        },
+    );
+    let depositor_token_account = config::get_claimer_token_account(&token_program_id, &token_mint_id, &depositor_wallet.pubkey());
+    add_token_account(&mut program_test, depositor_token_account, token_mint_id, depositor_wallet.pubkey(), 1000);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    send_deposit(&mut banks_client, &payer, recent_blockhash, program_id, token_mint_id, program_info.pool_account_id,
+                 &depositor_wallet, depositor_token_account, 300).await;
+    send_deposit(&mut banks_client, &payer, recent_blockhash, program_id, token_mint_id, program_info.pool_account_id,
+                 &depositor_wallet, depositor_token_account, 200).await;
+
+    // Tokens are in the pool
+    assert_eq!(get_token_amount(&mut banks_client, program_info.pool_token_account_id).await, 10 * 500 + 500);
+
+    // Deposits are recorded
+    assert_eq!(get_pool_state(&mut banks_client, program_info.pool_account_id).await.total_deposited, 500);
+
+    let depositor_account_id = config::get_depositor_account(&program_id, &program_info.pool_account_id, &depositor_wallet.pubkey()).0;
+    let depositor_account = banks_client.get_account(depositor_account_id)
+        .await
+        .expect("depositor_account get_account failed")
+        .expect("depositor_account not found");
+    let depositor_account_state = AirdropDepositor::unpack(&depositor_account.data).unwrap();
+    assert_eq!(depositor_account_state.pool_account, program_info.pool_account_id);
+    assert_eq!(depositor_account_state.depositor_wallet, depositor_wallet.pubkey());
+    assert_eq!(depositor_account_state.total_deposited, 500);
+}
+
+async fn send_deposit(banks_client: &mut BanksClient,
+                      payer: &Keypair,
+                      recent_blockhash: Hash,
+                      program_id: Pubkey,
+                      token_mint_id: Pubkey,
+                      pool_account_id: Pubkey,
+                      depositor_wallet: &Keypair,
+                      depositor_token_account: Pubkey,
+                      amount: u64) {
+    let keys = DepositAccountKeys {
+        depositor_wallet: depositor_wallet.pubkey(),
+        program: program_id,
+        rent_sysvar: solana_program::sysvar::rent::id(),
+        system_program: solana_program::system_program::id(),
+        token_program: spl_token::id(),
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        depositor_token_account,
+    };
+    let instruction = AirdropPoolInstruction::deposit(&keys, amount);
+    send_instruction(banks_client, payer, recent_blockhash, instruction, &[depositor_wallet]).await.unwrap();
+}
@@ -0,0 +1,146 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        config,
+        error::AirdropPoolError,
+        processor::process_instruction,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token,
+};
+
+use chikin_airdrop_pool::instruction::{AirdropPoolInstruction, DistributeAccountKeys};
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, get_claimer_state, get_pool_state, get_token_amount, send_claim, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_distribute() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let authority = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    program_test.add_program(
+        "AssociatedTokenProgram",
+        spl_associated_token_account::id(),
+        processor!(spl_associated_token_account::processor::process_instruction),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           2,
+                                           authority.pubkey());
+    let pool_account_id = program_info.pool_account_id;
+
+    // The authority funds the accounts of the recipients
+    program_test.add_account(
+        authority.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+
+    // Recipient without a token account
+    let user3_wallet_id = Pubkey::new_unique();
+    let user3_token_account_id = config::get_claimer_token_account(&token_program_id, &token_mint_id, &user3_wallet_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Only the authority distributes
+    let other_authority = Keypair::new();
+    let instruction = distribute_instruction(program_id, other_authority.pubkey(), token_mint_id, pool_account_id, &[(user1_info.wallet.pubkey(), 300)]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&other_authority]).await;
+    assert_error(result, AirdropPoolError::AuthorityKeyMismatch);
+
+    // Each recipient has an amount
+    let mut instruction = distribute_instruction(program_id, authority.pubkey(), token_mint_id, pool_account_id, &[(user1_info.wallet.pubkey(), 300)]);
+    instruction.data = AirdropPoolInstruction::Distribute { amounts: vec![300, 200] }.pack();
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+    assert_error(result, AirdropPoolError::DistributeAmountsMismatch);
+
+    // The pool can't distribute more than it holds
+    let instruction = distribute_instruction(program_id, authority.pubkey(), token_mint_id, pool_account_id, &[(user1_info.wallet.pubkey(), 10_000)]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+    assert_error(result, AirdropPoolError::InsufficientBalance);
+
+    let instruction = distribute_instruction(program_id, authority.pubkey(), token_mint_id, pool_account_id, &[
+        (user1_info.wallet.pubkey(), 300),
+        (user3_wallet_id, 200),
+    ]);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await.unwrap();
+
+    assert_eq!(get_token_amount(&mut banks_client, user1_info.token_account).await, 300);
+    assert_eq!(get_token_amount(&mut banks_client, user3_token_account_id).await, 200);
+
+    for wallet_id in [user1_info.wallet.pubkey(), user3_wallet_id] {
+        let claimer_account_id = config::get_claimer_account(&program_id, &pool_account_id, &wallet_id).0;
+        assert_eq!(get_claimer_state(&mut banks_client, claimer_account_id).await.claimed, 1);
+    }
+
+    let pool_account_state = get_pool_state(&mut banks_client, pool_account_id).await;
+    assert_eq!(pool_account_state.total_claimers, 2);
+    assert_eq!(pool_account_state.total_base_rewards_paid, 500);
+
+    // Recipients are marked as claimed
+    let instruction = distribute_instruction(program_id, authority.pubkey(), token_mint_id, pool_account_id, &[
+        (user2_info.wallet.pubkey(), 100),
+        (user1_info.wallet.pubkey(), 100),
+    ]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+    assert_error(result, AirdropPoolError::AlreadyClaimed);
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, pool_account_id);
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim, &user1_info).await;
+    assert_error(result, AirdropPoolError::InitClaimerAccountFailed);
+    assert_eq!(get_token_amount(&mut banks_client, user1_info.token_account).await, 300);
+}
+
+fn distribute_instruction(program_id: Pubkey,
+                          authority: Pubkey,
+                          token_mint_id: Pubkey,
+                          pool_account_id: Pubkey,
+                          recipient_list: &[(Pubkey, u64)]) -> Instruction {
+    let keys = DistributeAccountKeys {
+        authority,
+        program: program_id,
+        rent_sysvar: solana_program::sysvar::rent::id(),
+        clock_sysvar: solana_program::sysvar::clock::id(),
+        system_program: solana_program::system_program::id(),
+        token_program: spl_token::id(),
+        associated_token_program: spl_associated_token_account::id(),
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+    };
+    AirdropPoolInstruction::distribute(&keys, recipient_list)
+}
@@ -0,0 +1,210 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        config,
+        error::AirdropPoolError,
+        instruction::{INSTRUCTION_VERSION, InitializeArgs, LEGACY_PACKED_SIZE},
+        processor::process_instruction,
+        state::AirdropPool,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        signature::Signer,
+    },
+    spl_token,
+};
+
+use chikin_airdrop_pool::instruction::AirdropPoolInstruction;
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, get_claimer_state, get_token_amount, send_instruction};
+
+mod testutils;
+
+#[test]
+fn test_instruction_encoding() {
+    // Variable length instructions are not padded
+    let instruction = AirdropPoolInstruction::ClaimWithProof {
+        amount: 500,
+        proof: vec![[7; 32]; 4],
+    };
+    let data = instruction.pack();
+    assert_eq!(data[0], INSTRUCTION_VERSION);
+    assert_eq!(data.len(), 1 + 1 + 8 + 4 + 4 * 32);
+    assert_eq!(AirdropPoolInstruction::unpack(&data).unwrap(), instruction);
+
+    // Legacy Initialize, with the fields of deployed clients
+    let mut data = vec![0];
+    data.extend_from_slice(&[1, 0, 1, 0]);
+    data.extend_from_slice(&500u64.to_le_bytes());
+    data.extend_from_slice(&100u64.to_le_bytes());
+    data.push(2);
+    data.resize(LEGACY_PACKED_SIZE, 0);
+    assert_eq!(
+        AirdropPoolInstruction::unpack(&data).unwrap(),
+        AirdropPoolInstruction::Initialize(Box::new(InitializeArgs {
+            pool_account_nonce: [1, 0, 1, 0],
+            reward_per_account: 500,
+            reward_per_referral: 100,
+            max_referral_depth: 2,
+            claim_start_unix_timestamp: None,
+            claim_end_unix_timestamp: None,
+            merkle_root: None,
+            voucher_signer: None,
+            vesting_duration_seconds: None,
+            vesting_cliff_seconds: None,
+            referral_rewards: vec![],
+            gate_mint: None,
+            gate_min_amount: 0,
+            claim_fee_lamports: 0,
+            fee_recipient: Pubkey::default(),
+        })),
+    );
+
+    // Legacy Claim
+    let referrer = Pubkey::new_unique();
+    let data = legacy_claim_data(Some(referrer));
+    assert_eq!(
+        AirdropPoolInstruction::unpack(&data).unwrap(),
+        AirdropPoolInstruction::LegacyClaim { referrer: Some(referrer) },
+    );
+
+    // Malformed instructions
+    let data = AirdropPoolInstruction::Withdraw { amount: 100 }.pack();
+    let mut data_with_trailing_bytes = data.clone();
+    data_with_trailing_bytes.push(0);
+    let mut legacy_withdraw_data = data[1..].to_vec();
+    legacy_withdraw_data.resize(LEGACY_PACKED_SIZE, 0);
+    let malformed_data_list = vec![
+        vec![],
+        vec![INSTRUCTION_VERSION],
+        vec![INSTRUCTION_VERSION, 255],
+        vec![INSTRUCTION_VERSION + 1, 0],
+        data[..data.len() - 1].to_vec(),
+        data_with_trailing_bytes,
+        legacy_withdraw_data,
+        legacy_claim_data(None)[..LEGACY_PACKED_SIZE - 1].to_vec(),
+    ];
+    for malformed_data in malformed_data_list {
+        assert_eq!(AirdropPoolInstruction::unpack(&malformed_data).unwrap_err(), AirdropPoolError::InvalidInstruction);
+    }
+}
+
+#[tokio::test]
+async fn test_process_instruction_encoding() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           2,
+                                           Pubkey::new_unique());
+
+    let user_info = UserInfo::create(&mut program_test, program_id, token_mint_id, program_info.pool_account_id);
+    let referred_user_info = UserInfo::create(&mut program_test, program_id, token_mint_id, program_info.pool_account_id);
+
+    // Pool charging a claim fee
+    let fee_program_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [2, 0, 1, 0],
+        reward_per_account: 500,
+        claim_fee_lamports: 1000,
+        fee_recipient: Pubkey::new_unique(),
+        ..AirdropPool::default()
+    });
+    let fee_user_info = UserInfo::create(&mut program_test, program_id, token_mint_id, fee_program_info.pool_account_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let claim_instruction = ClaimBuilder::new(program_id, token_mint_id, program_info.pool_account_id)
+        .build(user_info.wallet.pubkey());
+
+    // Malformed data is rejected with an error instead of a panic
+    let instruction = Instruction {
+        data: vec![INSTRUCTION_VERSION, 1],
+        ..claim_instruction
+    };
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::InvalidInstruction);
+
+    // Deployed clients can't claim from pools requiring accounts they don't send
+    let instruction = legacy_claim_instruction(program_id, token_mint_id, fee_program_info.pool_account_id, &fee_user_info, &[]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&fee_user_info.wallet]).await;
+    assert_error(result, AirdropPoolError::LegacyClaimNotSupported);
+
+    // Deployed clients still send the legacy encoding, with the accounts of the first release
+    let instruction = legacy_claim_instruction(program_id, token_mint_id, program_info.pool_account_id, &user_info, &[]);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user_info.wallet]).await.unwrap();
+    assert_eq!(get_token_amount(&mut banks_client, user_info.token_account).await, 500);
+
+    let instruction = legacy_claim_instruction(program_id,
+                                               token_mint_id,
+                                               program_info.pool_account_id,
+                                               &referred_user_info,
+                                               &[user_info.wallet.pubkey()]);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&referred_user_info.wallet]).await.unwrap();
+    assert_eq!(get_token_amount(&mut banks_client, referred_user_info.token_account).await, 500 + 100);
+    assert_eq!(get_claimer_state(&mut banks_client, user_info.account).await.pending_referral_rewards, 100);
+    assert_eq!(get_claimer_state(&mut banks_client, referred_user_info.account).await.referrer_wallet, Some(user_info.wallet.pubkey()));
+}
+
+// Claim built like the clients of the first release
+fn legacy_claim_instruction(program_id: Pubkey,
+                            token_mint_id: Pubkey,
+                            pool_account_id: Pubkey,
+                            user_info: &UserInfo,
+                            referrer_wallet_list: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(program_id, false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(token_mint_id, false),
+        AccountMeta::new(pool_account_id, false),
+        AccountMeta::new(config::get_pool_token_account(&program_id, &pool_account_id).0, false),
+        AccountMeta::new(user_info.wallet.pubkey(), true),
+        AccountMeta::new(user_info.account, false),
+        AccountMeta::new(user_info.token_account, false),
+    ];
+    for referrer_wallet in referrer_wallet_list {
+        accounts.push(AccountMeta::new(*referrer_wallet, false));
+        accounts.push(AccountMeta::new(config::get_claimer_account(&program_id, &pool_account_id, referrer_wallet).0, false));
+        accounts.push(AccountMeta::new(config::get_claimer_token_account(&spl_token::id(), &token_mint_id, referrer_wallet), false));
+    }
+    Instruction::new_with_bytes(program_id, &legacy_claim_data(referrer_wallet_list.first().cloned()), accounts)
+}
+
+fn legacy_claim_data(referrer: Option<Pubkey>) -> Vec<u8> {
+    let mut data = vec![1];
+    match referrer {
+        Some(referrer) => {
+            data.push(1);
+            data.extend_from_slice(referrer.as_ref());
+        }
+        None => data.push(0),
+    }
+    data.resize(LEGACY_PACKED_SIZE, 0);
+    data
+}
@@ -0,0 +1,219 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        config,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::{AIRDROP_CLAIMER_V1, AIRDROP_POOL_V1, AirdropClaimer, AirdropClaimerV0, AirdropPool, AirdropPoolV0},
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        hash::Hash,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+    },
+};
+
+use chikin_airdrop_pool::instruction::AirdropPoolInstruction;
+use chikin_airdrop_pool::packable::Packable;
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, get_claimer_state, get_pool_state, send_claim, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_migrate_pool() {
+    let program_id = Pubkey::new_unique();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    // Program deployed with the upgradeable loader, whose upgrade authority signs the migration of pools created
+    // before the authority. ProgramData is the u32 variant index 3, the slot, then the optional upgrade authority
+    let upgrade_authority = Keypair::new();
+    let mut program_data = vec![3, 0, 0, 0];
+    program_data.extend_from_slice(&0u64.to_le_bytes());
+    program_data.push(1);
+    program_data.extend_from_slice(&upgrade_authority.pubkey().to_bytes());
+    program_test.add_account(
+        config::get_program_data_account(&program_id),
+        Account {
+            lamports: Rent::default().minimum_balance(program_data.len()),
+            data: program_data,
+            owner: solana_program::bpf_loader_upgradeable::id(),
+            ..Account::default()
+        },
+    );
+
+    // Pool created with the 85 bytes layout
+    let legacy_pool_account_state = AirdropPoolV0 {
+        token_program_id: spl_token::id(),
+        token_mint_id,
+        account_nonce: [1, 0, 1, 0],
+        reward_per_account: 500,
+        reward_per_referral: 100,
+        max_referral_depth: 2,
+    };
+    let pool_account_id = config::get_pool_account(&program_id, &token_mint_id, &legacy_pool_account_state.account_nonce).0;
+    program_test.add_account(
+        pool_account_id,
+        Account {
+            lamports: Rent::default().minimum_balance(AirdropPoolV0::PACKED_SIZE),
+            data: legacy_pool_account_state.pack(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Claimer created with the 34 bytes layout
+    let claimer_wallet_id = Pubkey::new_unique();
+    let legacy_claimer_account_state = AirdropClaimerV0 {
+        referrer_wallet: Some(Pubkey::new_unique()),
+        claimed: 1,
+    };
+    let claimer_account_id = config::get_claimer_account(&program_id, &pool_account_id, &claimer_wallet_id).0;
+    program_test.add_account(
+        claimer_account_id,
+        Account {
+            lamports: Rent::default().minimum_balance(AirdropClaimerV0::PACKED_SIZE),
+            data: legacy_claimer_account_state.pack(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    // Previous layouts are unpacked with their own type
+    assert_eq!(AirdropPool::unpack(&legacy_pool_account_state.pack()).unwrap(), legacy_pool_account_state.clone().into());
+    assert!(!AirdropPool::is_current_layout(&legacy_pool_account_state.pack()));
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let other_authority = Keypair::new();
+    let result = send_migrate_pool(&mut banks_client, &payer, recent_blockhash, program_id, pool_account_id, Some(&other_authority), &[claimer_wallet_id]).await;
+    assert_error(result, AirdropPoolError::UpgradeAuthorityMismatch);
+
+    send_migrate_pool(&mut banks_client, &payer, recent_blockhash, program_id, pool_account_id, Some(&upgrade_authority), &[claimer_wallet_id]).await.unwrap();
+
+    let pool_account = banks_client.get_account(pool_account_id)
+        .await
+        .expect("pool_account get_account failed")
+        .expect("pool_account not found");
+    assert_eq!(pool_account.data.len(), AirdropPool::PACKED_SIZE);
+    assert_eq!(pool_account.data[0], AIRDROP_POOL_V1);
+    assert!(pool_account.lamports >= Rent::default().minimum_balance(AirdropPool::PACKED_SIZE));
+    let pool_account_state = AirdropPool::unpack(&pool_account.data).unwrap();
+    assert_eq!(pool_account_state, AirdropPool {
+        authority: upgrade_authority.pubkey(),
+        ..legacy_pool_account_state.into()
+    });
+    assert_eq!(pool_account_state.total_claimers, 0);
+
+    let claimer_account = banks_client.get_account(claimer_account_id)
+        .await
+        .expect("claimer_account get_account failed")
+        .expect("claimer_account not found");
+    assert_eq!(claimer_account.data.len(), AirdropClaimer::PACKED_SIZE);
+    assert_eq!(claimer_account.data[0], AIRDROP_CLAIMER_V1);
+    assert!(claimer_account.lamports >= Rent::default().minimum_balance(AirdropClaimer::PACKED_SIZE));
+    let claimer_account_state = AirdropClaimer::unpack(&claimer_account.data).unwrap();
+    assert_eq!(claimer_account_state, legacy_claimer_account_state.into());
+    assert_eq!(claimer_account_state.pending_referral_rewards, 0);
+
+    // Pools and claimers are only migrated once
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    let result = send_migrate_pool(&mut banks_client, &payer, recent_blockhash, program_id, pool_account_id, None, &[]).await;
+    assert_error(result, AirdropPoolError::PoolAlreadyMigrated);
+
+    let result = send_migrate_pool(&mut banks_client, &payer, recent_blockhash, program_id, pool_account_id, None, &[claimer_wallet_id]).await;
+    assert_error(result, AirdropPoolError::ClaimerAlreadyMigrated);
+}
+
+#[tokio::test]
+async fn test_claim_migrates_referrer() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [1, 0, 1, 0],
+        reward_per_account: 500,
+        reward_per_referral: 100,
+        max_referral_depth: 1,
+        authority: Pubkey::new_unique(),
+        ..AirdropPool::default()
+    });
+    let pool_account_id = program_info.pool_account_id;
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+
+    // Referrer which claimed with the 34 bytes layout and was not migrated
+    program_test.add_account(
+        user1_info.account,
+        Account {
+            lamports: Rent::default().minimum_balance(AirdropClaimerV0::PACKED_SIZE),
+            data: AirdropClaimerV0 { referrer_wallet: None, claimed: 1 }.pack(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, pool_account_id)
+        .referrers(&[user1_info.wallet.pubkey()]);
+    send_claim(&mut banks_client, &payer, recent_blockhash, &claim, &user2_info).await.unwrap();
+
+    let referrer_account = banks_client.get_account(user1_info.account)
+        .await
+        .expect("referrer_account get_account failed")
+        .expect("referrer_account not found");
+    assert_eq!(referrer_account.data.len(), AirdropClaimer::PACKED_SIZE);
+    assert_eq!(referrer_account.data[0], AIRDROP_CLAIMER_V1);
+    assert!(referrer_account.lamports >= Rent::default().minimum_balance(AirdropClaimer::PACKED_SIZE));
+    let referrer_account_state = get_claimer_state(&mut banks_client, user1_info.account).await;
+    assert_eq!(referrer_account_state.claimed, 1);
+    assert_eq!(referrer_account_state.pending_referral_rewards, 100);
+    assert_eq!(referrer_account_state.direct_referrals, 1);
+    assert_eq!(get_pool_state(&mut banks_client, pool_account_id).await.reserved_amount, 100);
+}
+
+async fn send_migrate_pool(banks_client: &mut BanksClient,
+                           payer: &Keypair,
+                           recent_blockhash: Hash,
+                           program_id: Pubkey,
+                           pool_account_id: Pubkey,
+                           upgrade_authority: Option<&Keypair>,
+                           claimer_wallet_list: &[Pubkey]) -> Result<(), BanksClientError> {
+    let instruction = AirdropPoolInstruction::migrate_pool(
+        payer.pubkey(),
+        program_id,
+        solana_program::system_program::id(),
+        pool_account_id,
+        upgrade_authority.map(|upgrade_authority| upgrade_authority.pubkey()),
+        claimer_wallet_list,
+    );
+    let signers: Vec<&Keypair> = upgrade_authority.into_iter().collect();
+    send_instruction(banks_client, payer, recent_blockhash, instruction, &signers).await
+}
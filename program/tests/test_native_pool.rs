@@ -0,0 +1,213 @@
+use {
+    chikin_airdrop_pool::{
+        self,
+        config,
+        error::AirdropPoolError,
+        processor::process_instruction,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token,
+};
+
+use chikin_airdrop_pool::instruction::{AddRewardMintAccountKeys, AirdropPoolInstruction, ClosePoolAccountKeys, DepositAccountKeys, DistributeAccountKeys, WithdrawAccountKeys};
+use testutils::{ClaimBuilder, InitializeBuilder, UserInfo};
+use testutils::{assert_error, get_claimer_state, get_pool_state, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_native_pool() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = solana_program::system_program::id();
+    let token_mint_id = spl_token::native_mint::id();
+    let pool_account_nonce = [1, 0, 1, 0];
+    let (pool_account_id, _) = config::get_pool_account(&program_id, &token_mint_id, &pool_account_nonce);
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let destination_wallet = Keypair::new();
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Native SOL pools use the native mint
+    let instruction = initialize_instruction(program_id, payer.pubkey(), Pubkey::new_unique(), pool_account_nonce, 1_000_000);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::NativeMintKeyMismatch);
+
+    // Rewards must make an empty claimer wallet rent exempt
+    let instruction = initialize_instruction(program_id, payer.pubkey(), token_mint_id, pool_account_nonce, 1_000);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::RewardBelowRentExemption);
+
+    // The payer becomes the authority
+    let instruction = initialize_instruction(program_id, payer.pubkey(), token_mint_id, pool_account_nonce, 1_000_000);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await.unwrap();
+
+    let instruction = AirdropPoolInstruction::update_pool(payer.pubkey(), program_id, pool_account_id, 1_000, 500_000, 1);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::RewardBelowRentExemption);
+
+    let pool_rent = banks_client.get_balance(pool_account_id).await.unwrap();
+
+    // Deposits are transferred to the pool account
+    let keys = DepositAccountKeys {
+        depositor_wallet: payer.pubkey(),
+        program: program_id,
+        rent_sysvar: solana_program::sysvar::rent::id(),
+        system_program: solana_program::system_program::id(),
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        depositor_token_account: payer.pubkey(),
+    };
+    let instruction = AirdropPoolInstruction::deposit(&keys, 10_000_000);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await.unwrap();
+    assert_eq!(banks_client.get_balance(pool_account_id).await.unwrap(), pool_rent + 10_000_000);
+
+    // Distributed amounts must make an empty recipient wallet rent exempt
+    let keys = DistributeAccountKeys {
+        authority: payer.pubkey(),
+        program: program_id,
+        rent_sysvar: solana_program::sysvar::rent::id(),
+        clock_sysvar: solana_program::sysvar::clock::id(),
+        system_program: solana_program::system_program::id(),
+        token_program: token_program_id,
+        associated_token_program: spl_associated_token_account::id(),
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+    };
+    let instruction = AirdropPoolInstruction::distribute(&keys, &[(Pubkey::new_unique(), 1_000)]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::RewardBelowRentExemption);
+
+    // Claims pay the claimer wallets, the payer funds the claimer accounts
+    let user1_balance = banks_client.get_balance(user1_info.wallet.pubkey()).await.unwrap();
+    let instruction = claim_instruction(program_id, payer.pubkey(), pool_account_id, &user1_info, &[]);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user1_info.wallet]).await.unwrap();
+    assert_eq!(banks_client.get_balance(user1_info.wallet.pubkey()).await.unwrap(), user1_balance + 1_000_000);
+
+    let user2_balance = banks_client.get_balance(user2_info.wallet.pubkey()).await.unwrap();
+    let instruction = claim_instruction(program_id, payer.pubkey(), pool_account_id, &user2_info, &[user1_info.wallet.pubkey()]);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user2_info.wallet]).await.unwrap();
+    assert_eq!(banks_client.get_balance(user2_info.wallet.pubkey()).await.unwrap(), user2_balance + 1_500_000);
+
+    assert_eq!(get_claimer_state(&mut banks_client, user1_info.account).await.pending_referral_rewards, 500_000);
+
+    let instruction = AirdropPoolInstruction::withdraw_referral_rewards(
+        program_id,
+        token_program_id,
+        token_mint_id,
+        pool_account_id,
+        user1_info.wallet.pubkey(),
+        &[],
+    );
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await.unwrap();
+    assert_eq!(banks_client.get_balance(user1_info.wallet.pubkey()).await.unwrap(), user1_balance + 1_500_000);
+    assert_eq!(banks_client.get_balance(pool_account_id).await.unwrap(), pool_rent + 7_000_000);
+
+    // Native SOL pools can't pay reward mints
+    let keys = AddRewardMintAccountKeys {
+        authority: payer.pubkey(),
+        program: program_id,
+        rent_sysvar: solana_program::sysvar::rent::id(),
+        system_program: solana_program::system_program::id(),
+        token_program: token_program_id,
+        reward_mint: Pubkey::new_unique(),
+        pool_account: pool_account_id,
+    };
+    let instruction = AirdropPoolInstruction::add_reward_mint(&keys, 100, 0);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::RewardMintsNotSupported);
+
+    // Only the authority withdraws, and the pool account keeps its rent exemption
+    let other_authority = Keypair::new();
+    let instruction = withdraw_instruction(program_id, other_authority.pubkey(), pool_account_id, destination_wallet.pubkey(), 2_000_000);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&other_authority]).await;
+    assert_error(result, AirdropPoolError::AuthorityKeyMismatch);
+
+    let instruction = withdraw_instruction(program_id, payer.pubkey(), pool_account_id, destination_wallet.pubkey(), 7_000_001);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::InsufficientBalance);
+
+    // The pool account pays the withdrawal
+    let mut instruction = withdraw_instruction(program_id, payer.pubkey(), pool_account_id, destination_wallet.pubkey(), 2_000_000);
+    instruction.accounts[4].is_writable = false;
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::AccountNotWritable);
+
+    let instruction = withdraw_instruction(program_id, payer.pubkey(), pool_account_id, destination_wallet.pubkey(), 2_000_000);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await.unwrap();
+    assert_eq!(banks_client.get_balance(destination_wallet.pubkey()).await.unwrap(), 2_000_000);
+
+    let pool_account_state = get_pool_state(&mut banks_client, pool_account_id).await;
+    assert_eq!(pool_account_state.total_deposited, 10_000_000);
+    assert_eq!(pool_account_state.total_claimers, 2);
+
+    // Closing the pool sends the remaining lamports and the rent
+    let instruction = AirdropPoolInstruction::close_pool(&ClosePoolAccountKeys {
+        authority: payer.pubkey(),
+        program: program_id,
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        destination_token_account: destination_wallet.pubkey(),
+        destination_wallet: destination_wallet.pubkey(),
+        reward_mint_list: vec![],
+    });
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await.unwrap();
+    assert_eq!(banks_client.get_balance(destination_wallet.pubkey()).await.unwrap(), 2_000_000 + 5_000_000 + pool_rent);
+    assert!(banks_client.get_account(pool_account_id).await.unwrap().is_none());
+}
+
+fn initialize_instruction(program_id: Pubkey,
+                          authority: Pubkey,
+                          token_mint_id: Pubkey,
+                          pool_account_nonce: [u8; 4],
+                          reward_per_account: u64) -> Instruction {
+    InitializeBuilder {
+        token_program_id: solana_program::system_program::id(),
+        reward_per_account,
+        reward_per_referral: 500_000,
+        max_referral_depth: 1,
+        ..InitializeBuilder::new(program_id, token_mint_id, pool_account_nonce)
+    }.build(authority)
+}
+
+fn claim_instruction(program_id: Pubkey,
+                     payer: Pubkey,
+                     pool_account_id: Pubkey,
+                     user_info: &UserInfo,
+                     referrer_wallet_list: &[Pubkey]) -> Instruction {
+    ClaimBuilder::new(program_id, spl_token::native_mint::id(), pool_account_id)
+        .token_program(solana_program::system_program::id())
+        .payer(payer)
+        .referrers(referrer_wallet_list)
+        .build(user_info.wallet.pubkey())
+}
+
+fn withdraw_instruction(program_id: Pubkey,
+                        authority: Pubkey,
+                        pool_account_id: Pubkey,
+                        destination_wallet: Pubkey,
+                        amount: u64) -> Instruction {
+    AirdropPoolInstruction::withdraw(&WithdrawAccountKeys {
+        authority,
+        program: program_id,
+        token_program: solana_program::system_program::id(),
+        token_mint: spl_token::native_mint::id(),
+        pool_account: pool_account_id,
+        destination_token_account: destination_wallet,
+        reward_mint_list: vec![],
+    }, amount)
+}
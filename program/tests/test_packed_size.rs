@@ -1,9 +1,11 @@
 use solana_program_test::*;
 use solana_program::borsh::get_packed_len;
 use chikin_airdrop_pool;
-use chikin_airdrop_pool::instruction::AirdropPoolInstruction;
+use chikin_airdrop_pool::packable::Packable;
 use chikin_airdrop_pool::state::AirdropClaimer;
+use chikin_airdrop_pool::state::AirdropDepositor;
 use chikin_airdrop_pool::state::AirdropPool;
+use chikin_airdrop_pool::state::{AIRDROP_CLAIMER_RESERVED_SIZE, AIRDROP_POOL_RESERVED_SIZE};
 use solana_sdk::sysvar::rent::Rent;
 use solana_sdk::native_token::Sol;
 
@@ -12,11 +14,13 @@ async fn test_packed_size() {
     let rent = Rent::default();
     let airdrop_pool_len = get_packed_len::<AirdropPool>();
     let airdrop_claimer_len = get_packed_len::<AirdropClaimer>();
-    let airdrop_pool_instruction_len = get_packed_len::<AirdropPoolInstruction>();
+    let airdrop_depositor_len = get_packed_len::<AirdropDepositor>();
     println!("airdrop_pool_len={}", airdrop_pool_len);
     println!("airdrop_pool_min_balance_for_rent_exemption={}", Sol(rent.minimum_balance(airdrop_pool_len).max(1)));
     println!("airdrop_claimer_len={}", airdrop_claimer_len);
     println!("airdrop_claimer_min_balance_for_rent_exemption={}", Sol(rent.minimum_balance(airdrop_claimer_len).max(1)));
-    println!("airdrop_pool_instruction_len={}", airdrop_pool_instruction_len);
-    println!("airdrop_pool_instruction_min_balance_for_rent_exemption={}", Sol(rent.minimum_balance(airdrop_pool_instruction_len).max(1)));
+    // Accounts start with their discriminator, pools and claimers end with their reserved bytes
+    assert_eq!(1 + airdrop_pool_len + AIRDROP_POOL_RESERVED_SIZE, AirdropPool::PACKED_SIZE);
+    assert_eq!(1 + airdrop_claimer_len + AIRDROP_CLAIMER_RESERVED_SIZE, AirdropClaimer::PACKED_SIZE);
+    assert_eq!(1 + airdrop_depositor_len, AirdropDepositor::PACKED_SIZE);
 }
\ No newline at end of file
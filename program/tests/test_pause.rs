@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+};
+
+use chikin_airdrop_pool::instruction::AirdropPoolInstruction;
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, get_pool_state, send_claim, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_pause() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let authority = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           2,
+                                           authority.pubkey());
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, program_info.pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, program_info.pool_account_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, program_info.pool_account_id);
+
+    // Pause
+    let instruction = AirdropPoolInstruction::pause(authority.pubkey(), program_id, program_info.pool_account_id);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await.unwrap();
+    assert_eq!(get_pool_state(&mut banks_client, program_info.pool_account_id).await.paused, 1);
+
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim, &user1_info).await;
+    assert_error(result, AirdropPoolError::PoolPaused);
+
+    // Resume
+    let instruction = AirdropPoolInstruction::resume(authority.pubkey(), program_id, program_info.pool_account_id);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await.unwrap();
+    assert_eq!(get_pool_state(&mut banks_client, program_info.pool_account_id).await.paused, 0);
+
+    send_claim(&mut banks_client, &payer, recent_blockhash, &claim, &user2_info).await.unwrap();
+}
@@ -0,0 +1,66 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        processor::process_instruction,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::Signer,
+    },
+};
+
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{get_pool_state, send_claim};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_pool_stats() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           2,
+                                           Pubkey::new_unique());
+    let pool_account_id = program_info.pool_account_id;
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user3_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, pool_account_id);
+    send_claim(&mut banks_client, &payer, recent_blockhash, &claim, &user1_info).await.unwrap();
+    send_claim(&mut banks_client, &payer, recent_blockhash,
+               &claim.clone().referrers(&[user1_info.wallet.pubkey()]), &user2_info).await.unwrap();
+    send_claim(&mut banks_client, &payer, recent_blockhash,
+               &claim.clone().referrers(&[user2_info.wallet.pubkey(), user1_info.wallet.pubkey()]), &user3_info).await.unwrap();
+
+    let pool_account_state = get_pool_state(&mut banks_client, pool_account_id).await;
+    assert_eq!(pool_account_state.total_claimers, 3);
+    assert_eq!(pool_account_state.total_base_rewards_paid, 3 * 500);
+    // Referrers and referred claimers: (100 + 100) + (100 + 100 + 100)
+    assert_eq!(pool_account_state.total_referral_rewards_paid, 500);
+}
@@ -0,0 +1,96 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::AirdropClaimer,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        pubkey::Pubkey,
+        signature::Signer,
+    },
+    spl_token,
+};
+
+use chikin_airdrop_pool::packable::Packable;
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, send_claim};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_referral_cycle() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           3,
+                                           Pubkey::new_unique());
+    let pool_account_id = program_info.pool_account_id;
+
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+
+    // Claimers referring each other
+    let referrer1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let referrer2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    add_claimer_account(&mut program_test, program_id, &referrer1_info, referrer2_info.wallet.pubkey());
+    add_claimer_account(&mut program_test, program_id, &referrer2_info, referrer1_info.wallet.pubkey());
+
+    // Claimer referred by user2, which did not claim yet
+    let referrer3_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    add_claimer_account(&mut program_test, program_id, &referrer3_info, user2_info.wallet.pubkey());
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, pool_account_id);
+
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim.clone().referrers(&[user1_info.wallet.pubkey()]), &user1_info).await;
+    assert_error(result, AirdropPoolError::SelfReferral);
+
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim.clone().referrers(&[referrer1_info.wallet.pubkey(), referrer2_info.wallet.pubkey(), referrer1_info.wallet.pubkey()]), &user1_info).await;
+    assert_error(result, AirdropPoolError::ReferralCycle);
+
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim.clone().referrers(&[referrer3_info.wallet.pubkey(), user2_info.wallet.pubkey()]), &user2_info).await;
+    assert_error(result, AirdropPoolError::ReferralCycle);
+}
+
+fn add_claimer_account(program_test: &mut ProgramTest, program_id: Pubkey, user_info: &UserInfo, referrer_wallet: Pubkey) {
+    let account_state = AirdropClaimer {
+        referrer_wallet: Some(referrer_wallet),
+        claimed: 1,
+        ..AirdropClaimer::default()
+    };
+    program_test.add_account(
+        user_info.account,
+        Account {
+            lamports: 5,
+            data: account_state.pack(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+}
@@ -0,0 +1,146 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::AirdropPool,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token,
+};
+
+use chikin_airdrop_pool::instruction::{AirdropPoolInstruction, ClosePoolAccountKeys, WithdrawAccountKeys};
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, get_claimer_state, get_pool_state, get_token_amount, send_claim, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_referral_rewards() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let authority = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [1, 0, 1, 0],
+        reward_per_account: 500,
+        max_referral_depth: 3,
+        referral_rewards: Some([100, 40, 10, 0, 0, 0, 0, 0]),
+        authority: authority.pubkey(),
+        ..AirdropPool::default()
+    });
+    let pool_account_id = program_info.pool_account_id;
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user3_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user4_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, pool_account_id);
+    send_claim(&mut banks_client, &payer, recent_blockhash, &claim, &user1_info).await.unwrap();
+    send_claim(&mut banks_client, &payer, recent_blockhash,
+               &claim.clone().referrers(&[user1_info.wallet.pubkey()]), &user2_info).await.unwrap();
+    send_claim(&mut banks_client, &payer, recent_blockhash,
+               &claim.clone().referrers(&[user2_info.wallet.pubkey(), user1_info.wallet.pubkey()]), &user3_info).await.unwrap();
+    send_claim(&mut banks_client, &payer, recent_blockhash,
+               &claim.clone().referrers(&[user3_info.wallet.pubkey(), user2_info.wallet.pubkey(), user1_info.wallet.pubkey()]), &user4_info).await.unwrap();
+
+    // The referred claimer gets the direct referral reward, referrers withdraw theirs later
+    assert_eq!(get_token_amount(&mut banks_client, user1_info.token_account).await, 500);
+    assert_eq!(get_token_amount(&mut banks_client, user2_info.token_account).await, 500 + 100);
+    assert_eq!(get_token_amount(&mut banks_client, user3_info.token_account).await, 500 + 100);
+    assert_eq!(get_token_amount(&mut banks_client, user4_info.token_account).await, 500 + 100);
+
+    // Pending referral rewards stay reserved in the pool, 5000 - 500 - 3 * 600 = 2700 tokens with 390 reserved
+    assert_eq!(get_pool_state(&mut banks_client, pool_account_id).await.reserved_amount, 150 + 140 + 100);
+
+    let instruction = AirdropPoolInstruction::withdraw(&WithdrawAccountKeys {
+        authority: authority.pubkey(),
+        program: program_id,
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        destination_token_account: user4_info.token_account,
+        reward_mint_list: vec![],
+    }, 2700 - 390 + 1);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+    assert_error(result, AirdropPoolError::InsufficientBalance);
+
+    let instruction = AirdropPoolInstruction::close_pool(&ClosePoolAccountKeys {
+        authority: authority.pubkey(),
+        program: program_id,
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        destination_token_account: user4_info.token_account,
+        destination_wallet: authority.pubkey(),
+        reward_mint_list: vec![],
+    });
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+    assert_error(result, AirdropPoolError::PoolHasReservedRewards);
+
+    // Each referrer gets the reward of its depth
+    for user_info in &[&user1_info, &user2_info, &user3_info] {
+        send_withdraw_referral_rewards(&mut banks_client, &payer, recent_blockhash, program_id, token_mint_id, pool_account_id, user_info)
+            .await
+            .unwrap();
+    }
+    assert_eq!(get_token_amount(&mut banks_client, user1_info.token_account).await, 500 + 100 + 40 + 10);
+    assert_eq!(get_token_amount(&mut banks_client, user2_info.token_account).await, 500 + 100 + 100 + 40);
+    assert_eq!(get_token_amount(&mut banks_client, user3_info.token_account).await, 500 + 100 + 100);
+    assert_eq!(get_pool_state(&mut banks_client, pool_account_id).await.reserved_amount, 0);
+
+    // Referral statistics
+    let user1_account_state = get_claimer_state(&mut banks_client, user1_info.account).await;
+    assert_eq!(user1_account_state.direct_referrals, 1);
+    assert_eq!(user1_account_state.indirect_referrals, 2);
+    assert_eq!(user1_account_state.referral_rewards_earned, 100 + 40 + 10);
+    assert_eq!(user1_account_state.pending_referral_rewards, 0);
+    let user4_account_state = get_claimer_state(&mut banks_client, user4_info.account).await;
+    assert_eq!(user4_account_state.direct_referrals, 0);
+    assert_eq!(user4_account_state.referral_rewards_earned, 0);
+
+    let result = send_withdraw_referral_rewards(&mut banks_client, &payer, recent_blockhash, program_id, token_mint_id, pool_account_id, &user4_info).await;
+    assert_error(result, AirdropPoolError::NoPendingReferralRewards);
+}
+
+async fn send_withdraw_referral_rewards(banks_client: &mut BanksClient,
+                                        payer: &Keypair,
+                                        recent_blockhash: Hash,
+                                        program_id: Pubkey,
+                                        token_mint_id: Pubkey,
+                                        pool_account_id: Pubkey,
+                                        user_info: &UserInfo) -> Result<(), BanksClientError> {
+    let instruction = AirdropPoolInstruction::withdraw_referral_rewards(
+        program_id,
+        spl_token::id(),
+        token_mint_id,
+        pool_account_id,
+        user_info.wallet.pubkey(),
+        &[],
+    );
+    send_instruction(banks_client, payer, recent_blockhash, instruction, &[]).await
+}
@@ -0,0 +1,288 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        config,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::MAX_REWARD_MINTS,
+    },
+    solana_program::program_option::COption,
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        instruction::Instruction,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token::{self, state::Mint as SplTokenMint},
+};
+
+use chikin_airdrop_pool::instruction::{AddRewardMintAccountKeys, AirdropPoolInstruction, ClosePoolAccountKeys, WithdrawAccountKeys};
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, get_claimer_state, get_pool_state, get_token_amount, send_instruction, send_instructions};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_reward_mints() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let authority = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    program_test.add_program(
+        "AssociatedTokenProgram",
+        spl_associated_token_account::id(),
+        processor!(spl_associated_token_account::processor::process_instruction),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           2,
+                                           authority.pubkey());
+    let pool_account_id = program_info.pool_account_id;
+
+    // The authority funds the pool reward token accounts
+    program_test.add_account(
+        authority.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let reward_mint_id_list: Vec<Pubkey> = (0..MAX_REWARD_MINTS + 1)
+        .map(|_| add_mint(&mut program_test, &mint_authority))
+        .collect();
+    let reward_mint_id = reward_mint_id_list[0];
+
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user1_reward_token_account_id = config::get_claimer_token_account(&token_program_id, &reward_mint_id, &user1_info.wallet.pubkey());
+    let user2_reward_token_account_id = config::get_claimer_token_account(&token_program_id, &reward_mint_id, &user2_info.wallet.pubkey());
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Only the authority adds reward mints
+    let other_authority = Keypair::new();
+    let instruction = add_reward_mint_instruction(program_id, other_authority.pubkey(), reward_mint_id, pool_account_id);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&other_authority]).await;
+    assert_error(result, AirdropPoolError::AuthorityKeyMismatch);
+
+    let instruction = add_reward_mint_instruction(program_id, authority.pubkey(), reward_mint_id, pool_account_id);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await.unwrap();
+
+    // The pool mint and the added mints can't be added again
+    for mint_id in [token_mint_id, reward_mint_id] {
+        let instruction = add_reward_mint_instruction(program_id, authority.pubkey(), mint_id, pool_account_id);
+        let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+        assert_error(result, AirdropPoolError::RewardMintAlreadyAdded);
+    }
+
+    for mint_id in &reward_mint_id_list[1..MAX_REWARD_MINTS] {
+        let instruction = add_reward_mint_instruction(program_id, authority.pubkey(), *mint_id, pool_account_id);
+        send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await.unwrap();
+    }
+    let instruction = add_reward_mint_instruction(program_id, authority.pubkey(), reward_mint_id_list[MAX_REWARD_MINTS], pool_account_id);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+    assert_error(result, AirdropPoolError::TooManyRewardMints);
+
+    let pool_account_state = get_pool_state(&mut banks_client, pool_account_id).await;
+    let reward_mints = pool_account_state.get_reward_mints();
+    assert_eq!(reward_mints.len(), MAX_REWARD_MINTS);
+    assert_eq!(reward_mints[0].1.token_mint_id, reward_mint_id);
+    assert_eq!(reward_mints[0].1.reward_per_account, 50);
+    assert_eq!(reward_mints[0].1.reward_per_referral, 20);
+
+    // Fund the pool reward token accounts
+    for mint_id in &reward_mint_id_list[..MAX_REWARD_MINTS] {
+        let pool_reward_token_account_id = config::get_pool_reward_token_account(&program_id, &pool_account_id, mint_id).0;
+        let instruction = spl_token::instruction::mint_to(
+            &token_program_id,
+            mint_id,
+            &pool_reward_token_account_id,
+            &mint_authority.pubkey(),
+            &[],
+            1_000,
+        ).unwrap();
+        send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&mint_authority]).await.unwrap();
+    }
+
+    // Reward mints are passed in the order of the pool
+    let mut wrong_reward_mint_id_list = reward_mint_id_list[..MAX_REWARD_MINTS].to_vec();
+    wrong_reward_mint_id_list.swap(0, 1);
+    let instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &user1_info, &wrong_reward_mint_id_list, &[]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user1_info.wallet]).await;
+    assert_error(result, AirdropPoolError::RewardMintKeyMismatch);
+
+    // The claim pays every mint, creating the claimer reward token accounts
+    let instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &user1_info, &reward_mint_id_list[..MAX_REWARD_MINTS], &[]);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user1_info.wallet]).await.unwrap();
+
+    assert_eq!(get_token_amount(&mut banks_client, user1_info.token_account).await, 500);
+    assert_eq!(get_token_amount(&mut banks_client, user1_reward_token_account_id).await, 50);
+
+    // Referral rewards of the reward mints accumulate along with the pool mint ones
+    let instruction = claim_instruction(program_id, token_mint_id, pool_account_id, &user2_info, &reward_mint_id_list[..MAX_REWARD_MINTS], &[user1_info.wallet.pubkey()]);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user2_info.wallet]).await.unwrap();
+
+    assert_eq!(get_token_amount(&mut banks_client, user2_info.token_account).await, 600);
+    assert_eq!(get_token_amount(&mut banks_client, user2_reward_token_account_id).await, 70);
+
+    let claimer_account_state = get_claimer_state(&mut banks_client, user1_info.account).await;
+    assert_eq!(claimer_account_state.pending_referral_rewards, 100);
+    assert_eq!(claimer_account_state.pending_reward_mint_referral_rewards, [20; MAX_REWARD_MINTS]);
+
+    // Each reward mint reserves its pending referral rewards, Withdraw leaves them in the pool reward token account
+    let pool_account_state = get_pool_state(&mut banks_client, pool_account_id).await;
+    assert!(pool_account_state.get_reward_mints().iter().all(|(_, reward_mint)| reward_mint.reserved_amount == 20));
+
+    let authority_reward_token_account_id_list: Vec<Pubkey> = reward_mint_id_list[..MAX_REWARD_MINTS].iter()
+        .map(|mint_id| config::get_claimer_token_account(&token_program_id, mint_id, &authority.pubkey()))
+        .collect();
+    let mut instructions: Vec<Instruction> = reward_mint_id_list[..MAX_REWARD_MINTS].iter()
+        .map(|mint_id| spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            &authority.pubkey(),
+            mint_id,
+            &token_program_id,
+        ))
+        .collect();
+    instructions.push(AirdropPoolInstruction::withdraw(&WithdrawAccountKeys {
+        authority: authority.pubkey(),
+        program: program_id,
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        destination_token_account: user2_info.token_account,
+        reward_mint_list: vec![reward_mint_id],
+    }, 0));
+    send_instructions(&mut banks_client, &payer, recent_blockhash, &instructions, &[&authority]).await.unwrap();
+
+    let pool_reward_token_account_id = config::get_pool_reward_token_account(&program_id, &pool_account_id, &reward_mint_id).0;
+    assert_eq!(get_token_amount(&mut banks_client, authority_reward_token_account_id_list[0]).await, 1_000 - 50 - 70 - 20);
+    assert_eq!(get_token_amount(&mut banks_client, pool_reward_token_account_id).await, 20);
+
+    // Reserved rewards block closing the pool
+    let close_pool_keys = ClosePoolAccountKeys {
+        authority: authority.pubkey(),
+        program: program_id,
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        destination_token_account: user2_info.token_account,
+        destination_wallet: authority.pubkey(),
+        reward_mint_list: reward_mint_id_list[..MAX_REWARD_MINTS].to_vec(),
+    };
+    let instruction = AirdropPoolInstruction::close_pool(&close_pool_keys);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+    assert_error(result, AirdropPoolError::PoolHasReservedRewards);
+
+    let instruction = AirdropPoolInstruction::withdraw_referral_rewards(
+        program_id,
+        token_program_id,
+        token_mint_id,
+        pool_account_id,
+        user1_info.wallet.pubkey(),
+        &reward_mint_id_list[..MAX_REWARD_MINTS],
+    );
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await.unwrap();
+
+    assert_eq!(get_token_amount(&mut banks_client, user1_info.token_account).await, 600);
+    assert_eq!(get_token_amount(&mut banks_client, user1_reward_token_account_id).await, 70);
+
+    let claimer_account_state = get_claimer_state(&mut banks_client, user1_info.account).await;
+    assert_eq!(claimer_account_state.pending_referral_rewards, 0);
+    assert_eq!(claimer_account_state.pending_reward_mint_referral_rewards, [0; MAX_REWARD_MINTS]);
+    let pool_account_state = get_pool_state(&mut banks_client, pool_account_id).await;
+    assert!(pool_account_state.get_reward_mints().iter().all(|(_, reward_mint)| reward_mint.reserved_amount == 0));
+
+    // Closing the pool empties and closes every pool reward token account, which must all be passed
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    let instruction = AirdropPoolInstruction::close_pool(&ClosePoolAccountKeys {
+        reward_mint_list: reward_mint_id_list[..MAX_REWARD_MINTS - 1].to_vec(),
+        ..close_pool_keys.clone()
+    });
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await;
+    assert!(result.is_err());
+
+    let instruction = AirdropPoolInstruction::close_pool(&close_pool_keys);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await.unwrap();
+
+    assert_eq!(get_token_amount(&mut banks_client, authority_reward_token_account_id_list[0]).await, 1_000 - 50 - 70 - 20);
+    for (mint_id, authority_reward_token_account_id) in reward_mint_id_list[1..MAX_REWARD_MINTS].iter().zip(&authority_reward_token_account_id_list[1..]) {
+        assert_eq!(get_token_amount(&mut banks_client, *authority_reward_token_account_id).await, 1_000 - 50 - 70 - 20);
+        let pool_reward_token_account_id = config::get_pool_reward_token_account(&program_id, &pool_account_id, mint_id).0;
+        assert!(banks_client.get_account(pool_reward_token_account_id).await.unwrap().is_none());
+    }
+    assert!(banks_client.get_account(pool_reward_token_account_id).await.unwrap().is_none());
+}
+
+fn add_mint(program_test: &mut ProgramTest, mint_authority: &Keypair) -> Pubkey {
+    let mint_id = Pubkey::new_unique();
+    let mut data_packed = vec![0; SplTokenMint::LEN];
+    SplTokenMint {
+        mint_authority: COption::Some(mint_authority.pubkey()),
+        is_initialized: true,
+        ..SplTokenMint::default()
+    }.pack_into_slice(&mut data_packed);
+    program_test.add_account(
+        mint_id,
+        Account {
+            lamports: 1_000_000_000,
+            data: data_packed,
+            owner: spl_token::id(),
+            ..Account::default()
+        },
+    );
+    mint_id
+}
+
+fn add_reward_mint_instruction(program_id: Pubkey,
+                               authority: Pubkey,
+                               reward_mint_id: Pubkey,
+                               pool_account_id: Pubkey) -> Instruction {
+    let keys = AddRewardMintAccountKeys {
+        authority,
+        program: program_id,
+        rent_sysvar: solana_program::sysvar::rent::id(),
+        system_program: solana_program::system_program::id(),
+        token_program: spl_token::id(),
+        reward_mint: reward_mint_id,
+        pool_account: pool_account_id,
+    };
+    AirdropPoolInstruction::add_reward_mint(&keys, 50, 20)
+}
+
+fn claim_instruction(program_id: Pubkey,
+                     token_mint_id: Pubkey,
+                     pool_account_id: Pubkey,
+                     user_info: &UserInfo,
+                     reward_mint_id_list: &[Pubkey],
+                     referrer_wallet_list: &[Pubkey]) -> Instruction {
+    ClaimBuilder::new(program_id, token_mint_id, pool_account_id)
+        .reward_mints(reward_mint_id_list)
+        .referrers(referrer_wallet_list)
+        .build(user_info.wallet.pubkey())
+}
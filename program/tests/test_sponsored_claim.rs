@@ -0,0 +1,74 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        config,
+        processor::process_instruction,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token,
+};
+
+use testutils::ClaimBuilder;
+use testutils::ProgramInfo;
+use testutils::{get_claimer_state, get_token_amount, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_sponsored_claim() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    program_test.add_program(
+        "AssociatedTokenProgram",
+        spl_associated_token_account::id(),
+        processor!(spl_associated_token_account::processor::process_instruction),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           2,
+                                           Pubkey::new_unique());
+
+    // Brand-new claimer, without SOL nor token account
+    let claimer_wallet = Keypair::new();
+    let claimer_account_id = config::get_claimer_account(&program_id, &program_info.pool_account_id, &claimer_wallet.pubkey()).0;
+    let claimer_token_account_id = config::get_claimer_token_account(&token_program_id, &token_mint_id, &claimer_wallet.pubkey());
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // The relayer pays the transaction fee and the rent, the claimer only signs
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, program_info.pool_account_id)
+        .payer(payer.pubkey())
+        .build(claimer_wallet.pubkey());
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&claimer_wallet]).await.unwrap();
+
+    assert_eq!(get_claimer_state(&mut banks_client, claimer_account_id).await.claimed, 1);
+    assert_eq!(get_token_amount(&mut banks_client, claimer_token_account_id).await, 500);
+
+    assert_eq!(banks_client.get_balance(claimer_wallet.pubkey()).await.unwrap(), 0);
+}
@@ -0,0 +1,201 @@
+use {
+    chikin_airdrop_pool::{
+        self,
+        config,
+        error::AirdropPoolError,
+        processor::process_instruction,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::Account,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_instruction,
+    },
+    spl_token_2022::{
+        extension::{ExtensionType, transfer_fee},
+        state::Mint as TokenMint,
+    },
+};
+
+use chikin_airdrop_pool::instruction::{AirdropPoolInstruction, ClosePoolAccountKeys, DistributeAccountKeys};
+use testutils::{ClaimBuilder, InitializeBuilder};
+use testutils::{assert_error, get_pool_state, get_token_amount, send_instruction, send_instructions};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_token_2022() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token_2022::id();
+    let token_mint = Keypair::new();
+    let token_mint_id = token_mint.pubkey();
+    let pool_account_nonce = [1, 0, 1, 0];
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "Token2022Program",
+        token_program_id,
+        processor!(spl_token_2022::processor::Processor::process),
+    );
+
+    program_test.add_program(
+        "AssociatedTokenProgram",
+        spl_associated_token_account::id(),
+        processor!(spl_associated_token_account::processor::process_instruction),
+    );
+
+    let user1_wallet = Keypair::new();
+    let user2_wallet = Keypair::new();
+    for wallet in &[&user1_wallet, &user2_wallet] {
+        program_test.add_account(
+            wallet.pubkey(),
+            Account {
+                lamports: 10_000_000,
+                ..Account::default()
+            },
+        );
+    }
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Mint with a 1% transfer fee
+    let mint_len = ExtensionType::get_account_len::<TokenMint>(&[ExtensionType::TransferFeeConfig]);
+    let instructions = vec![
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &token_mint_id,
+            Rent::default().minimum_balance(mint_len),
+            mint_len as u64,
+            &token_program_id,
+        ),
+        transfer_fee::instruction::initialize_transfer_fee_config(
+            &token_program_id,
+            &token_mint_id,
+            None,
+            None,
+            100,
+            1_000_000,
+        ).unwrap(),
+        spl_token_2022::instruction::initialize_mint(
+            &token_program_id,
+            &token_mint_id,
+            &payer.pubkey(),
+            None,
+            0,
+        ).unwrap(),
+    ];
+    send_instructions(&mut banks_client, &payer, recent_blockhash, &instructions, &[&token_mint]).await.unwrap();
+
+    // Pool funded with 10_000 tokens
+    let pool_account_id = config::get_pool_account(&program_id, &token_mint_id, &pool_account_nonce).0;
+    let pool_token_account_id = config::get_pool_token_account(&program_id, &pool_account_id).0;
+    let instructions = vec![
+        InitializeBuilder {
+            token_program_id,
+            reward_per_account: 500,
+            reward_per_referral: 100,
+            max_referral_depth: 1,
+            ..InitializeBuilder::new(program_id, token_mint_id, pool_account_nonce)
+        }.build(payer.pubkey()),
+        spl_token_2022::instruction::mint_to(
+            &token_program_id,
+            &token_mint_id,
+            &pool_token_account_id,
+            &payer.pubkey(),
+            &[],
+            10_000,
+        ).unwrap(),
+    ];
+    send_instructions(&mut banks_client, &payer, recent_blockhash, &instructions, &[]).await.unwrap();
+
+    assert_eq!(get_pool_state(&mut banks_client, pool_account_id).await.token_program_id, token_program_id);
+
+    // Claims must use the token program of the pool
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, pool_account_id)
+        .build(user1_wallet.pubkey());
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user1_wallet]).await;
+    assert_error(result, AirdropPoolError::TokenProgramKeyMismatch);
+
+    // The claimer receives its full reward, the pool pays the transfer fee
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, pool_account_id)
+        .token_program(token_program_id)
+        .build(user2_wallet.pubkey());
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user2_wallet]).await.unwrap();
+
+    let user2_token_account_id = config::get_claimer_token_account(&token_program_id, &token_mint_id, &user2_wallet.pubkey());
+    assert_eq!(get_token_amount(&mut banks_client, user2_token_account_id).await, 500);
+    assert_eq!(get_token_amount(&mut banks_client, pool_token_account_id).await, 10_000 - 506);
+
+    // Referral rewards are reserved with the transfer fee of their payout
+    let instruction = ClaimBuilder::new(program_id, token_mint_id, pool_account_id)
+        .token_program(token_program_id)
+        .referrers(&[user2_wallet.pubkey()])
+        .build(user1_wallet.pubkey());
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&user1_wallet]).await.unwrap();
+
+    let user1_token_account_id = config::get_claimer_token_account(&token_program_id, &token_mint_id, &user1_wallet.pubkey());
+    assert_eq!(get_token_amount(&mut banks_client, user1_token_account_id).await, 600);
+    assert_eq!(get_pool_state(&mut banks_client, pool_account_id).await.reserved_amount, 102);
+
+    let instruction = AirdropPoolInstruction::withdraw_referral_rewards(
+        program_id,
+        token_program_id,
+        token_mint_id,
+        pool_account_id,
+        user2_wallet.pubkey(),
+        &[],
+    );
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await.unwrap();
+
+    assert_eq!(get_token_amount(&mut banks_client, user2_token_account_id).await, 600);
+    assert_eq!(get_pool_state(&mut banks_client, pool_account_id).await.reserved_amount, 0);
+    assert_eq!(get_token_amount(&mut banks_client, pool_token_account_id).await, 10_000 - 506 - 607 - 102);
+
+    // Distribute covers the transfer fee of each recipient, 2 * 4394 tokens are needed for 2 * 4350
+    let keys = DistributeAccountKeys {
+        authority: payer.pubkey(),
+        program: program_id,
+        rent_sysvar: solana_program::sysvar::rent::id(),
+        clock_sysvar: solana_program::sysvar::clock::id(),
+        system_program: solana_program::system_program::id(),
+        token_program: token_program_id,
+        associated_token_program: spl_associated_token_account::id(),
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+    };
+    let instruction = AirdropPoolInstruction::distribute(&keys, &[(Pubkey::new_unique(), 4350), (Pubkey::new_unique(), 4350)]);
+    let result = send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::InsufficientBalance);
+
+    // Closing sends the remaining tokens to the authority
+    let authority_token_account_id = config::get_claimer_token_account(&token_program_id, &token_mint_id, &payer.pubkey());
+    let instructions = vec![
+        spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &token_mint_id,
+            &token_program_id,
+        ),
+        AirdropPoolInstruction::close_pool(&ClosePoolAccountKeys {
+            authority: payer.pubkey(),
+            program: program_id,
+            token_program: token_program_id,
+            token_mint: token_mint_id,
+            pool_account: pool_account_id,
+            destination_token_account: authority_token_account_id,
+            destination_wallet: payer.pubkey(),
+            reward_mint_list: vec![],
+        }),
+    ];
+    send_instructions(&mut banks_client, &payer, recent_blockhash, &instructions, &[]).await.unwrap();
+
+    assert!(banks_client.get_account(pool_token_account_id).await.unwrap().is_none());
+    assert_eq!(get_token_amount(&mut banks_client, authority_token_account_id).await, 8785 - 88);
+}
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::AirdropPool,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::Signer,
+    },
+    spl_token,
+};
+
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{add_token_account, assert_error, get_token_amount, send_claim};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_token_gate() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let gate_mint_id = Pubkey::new_unique();
+    let gate_min_amount = 10;
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [1, 0, 1, 0],
+        reward_per_account: 500,
+        gate_mint: Some(gate_mint_id),
+        gate_min_amount,
+        ..AirdropPool::default()
+    });
+    let pool_account_id = program_info.pool_account_id;
+
+    // Holder of the minimum amount
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user1_gate_token_account_id = Pubkey::new_unique();
+    add_token_account(&mut program_test, user1_gate_token_account_id, gate_mint_id, user1_info.wallet.pubkey(), gate_min_amount);
+
+    // Holder of less than the minimum amount
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_gate_token_account_id = Pubkey::new_unique();
+    add_token_account(&mut program_test, user2_gate_token_account_id, gate_mint_id, user2_info.wallet.pubkey(), gate_min_amount - 1);
+
+    // Token account of another mint
+    let user3_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user3_gate_token_account_id = Pubkey::new_unique();
+    add_token_account(&mut program_test, user3_gate_token_account_id, Pubkey::new_unique(), user3_info.wallet.pubkey(), gate_min_amount);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, pool_account_id);
+
+    // The gate token account is required
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim, &user2_info).await;
+    assert_error(result, AirdropPoolError::GateRequirementNotMet);
+
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim.clone().gate_token_account(user2_gate_token_account_id), &user2_info).await;
+    assert_error(result, AirdropPoolError::GateRequirementNotMet);
+
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim.clone().gate_token_account(user3_gate_token_account_id), &user3_info).await;
+    assert_error(result, AirdropPoolError::GateRequirementNotMet);
+
+    // The gate token account must be owned by the claimer
+    let result = send_claim(&mut banks_client, &payer, recent_blockhash, &claim.clone().gate_token_account(user1_gate_token_account_id), &user2_info).await;
+    assert_error(result, AirdropPoolError::GateRequirementNotMet);
+
+    send_claim(&mut banks_client, &payer, recent_blockhash, &claim.clone().gate_token_account(user1_gate_token_account_id), &user1_info).await.unwrap();
+
+    assert_eq!(get_token_amount(&mut banks_client, user1_info.token_account).await, 500);
+}
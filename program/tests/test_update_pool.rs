@@ -0,0 +1,120 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::AirdropPool,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+};
+
+use chikin_airdrop_pool::instruction::AirdropPoolInstruction;
+use testutils::ProgramInfo;
+use testutils::{assert_error, get_pool_state, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_update_pool() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let authority = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           500,
+                                           100,
+                                           2,
+                                           authority.pubkey());
+    let referral_rewards_pool_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [2, 0, 1, 0],
+        reward_per_account: 500,
+        max_referral_depth: 2,
+        referral_rewards: Some([100, 40, 0, 0, 0, 0, 0, 0]),
+        authority: authority.pubkey(),
+        ..AirdropPool::default()
+    });
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Only the authority can update the pool
+    let result = send_update_pool(&mut banks_client,
+                                  &payer,
+                                  recent_blockhash,
+                                  program_id,
+                                  program_info.pool_account_id,
+                                  &Keypair::new(),
+                                  1000, 200, 3).await;
+    assert_error(result, AirdropPoolError::AuthorityKeyMismatch);
+
+    send_update_pool(&mut banks_client,
+                     &payer,
+                     recent_blockhash,
+                     program_id,
+                     program_info.pool_account_id,
+                     &authority,
+                     1000, 200, 3).await.unwrap();
+
+    let pool_account_state = get_pool_state(&mut banks_client, program_info.pool_account_id).await;
+    assert_eq!(pool_account_state.reward_per_account, 1000);
+    assert_eq!(pool_account_state.reward_per_referral, 200);
+    assert_eq!(pool_account_state.max_referral_depth, 3);
+    assert_eq!(pool_account_state.authority, authority.pubkey());
+
+    // The depth of a pool with a referral rewards table must stay the length of the table
+    let result = send_update_pool(&mut banks_client,
+                                  &payer,
+                                  recent_blockhash,
+                                  program_id,
+                                  referral_rewards_pool_info.pool_account_id,
+                                  &authority,
+                                  1000, 200, 3).await;
+    assert_error(result, AirdropPoolError::InvalidReferralRewards);
+
+    send_update_pool(&mut banks_client,
+                     &payer,
+                     recent_blockhash,
+                     program_id,
+                     referral_rewards_pool_info.pool_account_id,
+                     &authority,
+                     1000, 200, 2).await.unwrap();
+}
+
+async fn send_update_pool(banks_client: &mut BanksClient,
+                          payer: &Keypair,
+                          recent_blockhash: Hash,
+                          program_id: Pubkey,
+                          pool_account_id: Pubkey,
+                          authority: &Keypair,
+                          reward_per_account: u64,
+                          reward_per_referral: u64,
+                          max_referral_depth: u8) -> Result<(), BanksClientError> {
+    let instruction = AirdropPoolInstruction::update_pool(
+        authority.pubkey(),
+        program_id,
+        pool_account_id,
+        reward_per_account,
+        reward_per_referral,
+        max_referral_depth,
+    );
+    send_instruction(banks_client, payer, recent_blockhash, instruction, &[authority]).await
+}
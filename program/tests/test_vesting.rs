@@ -0,0 +1,124 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        error::AirdropPoolError,
+        processor::process_instruction,
+        state::AirdropPool,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token,
+};
+
+use chikin_airdrop_pool::instruction::{AirdropPoolInstruction, ClosePoolAccountKeys};
+use testutils::{ClaimBuilder, ProgramInfo, UserInfo};
+use testutils::{assert_error, get_pool_state, get_token_amount, send_context_instruction, set_unix_timestamp};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_vesting() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let authority = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let program_info = ProgramInfo::create_with_state(&mut program_test, &program_id, AirdropPool {
+        token_program_id,
+        token_mint_id,
+        account_nonce: [1, 0, 1, 0],
+        reward_per_account: 500,
+        reward_per_referral: 100,
+        max_referral_depth: 1,
+        vesting_duration_seconds: Some(1000),
+        vesting_cliff_seconds: Some(100),
+        authority: authority.pubkey(),
+        ..AirdropPool::default()
+    });
+    let pool_account_id = program_info.pool_account_id;
+    let user1_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+    let user2_info = UserInfo::create(&mut program_test, program_id, token_mint_id, pool_account_id);
+
+    let mut context = program_test.start_with_context().await;
+    let vesting_start = set_unix_timestamp(&mut context, 2, None).await;
+
+    let claim = ClaimBuilder::new(program_id, token_mint_id, pool_account_id);
+
+    // Claims don't transfer anything
+    let instruction = claim.build(user1_info.wallet.pubkey());
+    send_context_instruction(&mut context, instruction, &[&user1_info.wallet]).await.unwrap();
+    let instruction = claim.clone().referrers(&[user1_info.wallet.pubkey()]).build(user2_info.wallet.pubkey());
+    send_context_instruction(&mut context, instruction, &[&user2_info.wallet]).await.unwrap();
+    assert_eq!(get_token_amount(&mut context.banks_client, user1_info.token_account).await, 0);
+    assert_eq!(get_token_amount(&mut context.banks_client, user2_info.token_account).await, 0);
+
+    // Unreleased rewards are reserved, the pool can't be closed
+    assert_eq!(get_pool_state(&mut context.banks_client, pool_account_id).await.reserved_amount, 500 + 100 + 600);
+    let instruction = AirdropPoolInstruction::close_pool(&ClosePoolAccountKeys {
+        authority: authority.pubkey(),
+        program: program_id,
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: pool_account_id,
+        destination_token_account: user1_info.token_account,
+        destination_wallet: authority.pubkey(),
+        reward_mint_list: vec![],
+    });
+    let result = send_context_instruction(&mut context, instruction, &[&authority]).await;
+    assert_error(result, AirdropPoolError::PoolHasReservedRewards);
+
+    // Nothing is released before the cliff
+    set_unix_timestamp(&mut context, 4, Some(vesting_start + 50)).await;
+    let instruction = release_instruction(program_id, token_mint_id, pool_account_id, &user1_info);
+    let result = send_context_instruction(&mut context, instruction, &[]).await;
+    assert_error(result, AirdropPoolError::NothingToRelease);
+
+    // Linear release, the referral reward follows the referrer schedule
+    set_unix_timestamp(&mut context, 6, Some(vesting_start + 500)).await;
+    let instruction = release_instruction(program_id, token_mint_id, pool_account_id, &user1_info);
+    send_context_instruction(&mut context, instruction, &[]).await.unwrap();
+    assert_eq!(get_token_amount(&mut context.banks_client, user1_info.token_account).await, 300);
+    assert_eq!(get_pool_state(&mut context.banks_client, pool_account_id).await.reserved_amount, 1200 - 300);
+
+    set_unix_timestamp(&mut context, 8, Some(vesting_start + 2000)).await;
+    let instruction = release_instruction(program_id, token_mint_id, pool_account_id, &user1_info);
+    send_context_instruction(&mut context, instruction, &[]).await.unwrap();
+    assert_eq!(get_token_amount(&mut context.banks_client, user1_info.token_account).await, 600);
+
+    let instruction = release_instruction(program_id, token_mint_id, pool_account_id, &user2_info);
+    send_context_instruction(&mut context, instruction, &[]).await.unwrap();
+    assert_eq!(get_token_amount(&mut context.banks_client, user2_info.token_account).await, 600);
+    assert_eq!(get_pool_state(&mut context.banks_client, pool_account_id).await.reserved_amount, 0);
+}
+
+fn release_instruction(program_id: Pubkey,
+                       token_mint_id: Pubkey,
+                       pool_account_id: Pubkey,
+                       user_info: &UserInfo) -> Instruction {
+    AirdropPoolInstruction::release(
+        program_id,
+        solana_program::sysvar::clock::id(),
+        spl_token::id(),
+        token_mint_id,
+        pool_account_id,
+        user_info.wallet.pubkey(),
+    )
+}
@@ -0,0 +1,85 @@
+use std::str::FromStr;
+
+use {
+    chikin_airdrop_pool::{
+        self,
+        processor::process_instruction,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    spl_token,
+};
+
+use chikin_airdrop_pool::instruction::{AirdropPoolInstruction, ClosePoolAccountKeys, WithdrawAccountKeys};
+use testutils::ProgramInfo;
+use testutils::UserInfo;
+use testutils::{get_token_amount, send_instruction};
+
+mod testutils;
+
+#[tokio::test]
+async fn test_withdraw_and_close_pool() {
+    let program_id = Pubkey::new_unique();
+    let token_program_id = spl_token::id();
+    let token_mint_id = Pubkey::from_str("ALaYfBMScNrJxKTfgpfFYDQSMYJHpzuxGq15TM2j6o8E").unwrap();
+    let authority = Keypair::new();
+
+    let mut program_test = ProgramTest::new(
+        "ChikinProgram", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction),
+    );
+
+    program_test.add_program(
+        "TokenProgram",
+        token_program_id,
+        processor!(spl_token::processor::Processor::process),
+    );
+
+    let reward_per_account = 500;
+    let program_info = ProgramInfo::create(&mut program_test,
+                                           &program_id,
+                                           token_program_id,
+                                           token_mint_id,
+                                           [1, 0, 1, 0],
+                                           reward_per_account,
+                                           100,
+                                           2,
+                                           authority.pubkey());
+    let destination_info = UserInfo::create(&mut program_test, program_id, token_mint_id, program_info.pool_account_id);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Withdraw part of the vault
+    let instruction = AirdropPoolInstruction::withdraw(&WithdrawAccountKeys {
+        authority: authority.pubkey(),
+        program: program_id,
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: program_info.pool_account_id,
+        destination_token_account: destination_info.token_account,
+        reward_mint_list: vec![],
+    }, reward_per_account);
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await.unwrap();
+    assert_eq!(get_token_amount(&mut banks_client, destination_info.token_account).await, reward_per_account);
+
+    // Close the pool and recover everything left
+    let instruction = AirdropPoolInstruction::close_pool(&ClosePoolAccountKeys {
+        authority: authority.pubkey(),
+        program: program_id,
+        token_program: token_program_id,
+        token_mint: token_mint_id,
+        pool_account: program_info.pool_account_id,
+        destination_token_account: destination_info.token_account,
+        destination_wallet: destination_info.wallet.pubkey(),
+        reward_mint_list: vec![],
+    });
+    send_instruction(&mut banks_client, &payer, recent_blockhash, instruction, &[&authority]).await.unwrap();
+    assert_eq!(get_token_amount(&mut banks_client, destination_info.token_account).await, 10 * reward_per_account);
+
+    assert!(banks_client.get_account(program_info.pool_account_id).await.unwrap().is_none());
+    assert!(banks_client.get_account(program_info.pool_token_account_id).await.unwrap().is_none());
+}
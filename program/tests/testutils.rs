@@ -1,11 +1,21 @@
+#![allow(dead_code)]
+
 use solana_program_test::*;
 use solana_sdk::account::Account;
+use solana_sdk::clock::{Clock, UnixTimestamp};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{Instruction, InstructionError};
 use solana_sdk::program_pack::Pack;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::{Transaction, TransactionError};
 use spl_token;
 use spl_token::state::Account as SplTokenAccount;
+use spl_token::state::Mint as SplTokenMint;
+use spl_token_2022::extension::StateWithExtensions;
 
 use chikin_airdrop_pool::config;
+use chikin_airdrop_pool::error::AirdropPoolError;
+use chikin_airdrop_pool::instruction::{AirdropPoolInstruction, ClaimAccountKeys, InitializeArgs};
 use chikin_airdrop_pool::state::AirdropClaimer;
 use chikin_airdrop_pool::state::AirdropPool;
 use chikin_airdrop_pool::packable::Packable;
@@ -25,10 +35,8 @@ impl ProgramInfo {
                   reward_per_account: u64,
                   reward_per_referral: u64,
                   max_referral_depth: u8,
+                  authority: Pubkey,
     ) -> ProgramInfo {
-        let (account_id, _) = config::get_pool_account(&program_id, &token_mint_id, &pool_account_nonce);
-        let token_account_id = config::get_pool_token_account(&program_id, &account_id).0;
-
         let account_state = AirdropPool {
             token_program_id,
             token_mint_id,
@@ -36,11 +44,23 @@ impl ProgramInfo {
             reward_per_account,
             reward_per_referral,
             max_referral_depth,
+            authority,
+            ..AirdropPool::default()
         };
 
+        ProgramInfo::create_with_state(program_test, program_id, account_state)
+    }
+
+    pub fn create_with_state(program_test: &mut ProgramTest,
+                             program_id: &Pubkey,
+                             account_state: AirdropPool,
+    ) -> ProgramInfo {
+        let (account_id, _) = config::get_pool_account(&program_id, &account_state.token_mint_id, &account_state.account_nonce);
+        let token_account_id = config::get_pool_token_account(&program_id, &account_id).0;
+
         let token_account_state = SplTokenAccount {
-            mint: token_mint_id,
-            amount: 10 * reward_per_account,
+            mint: account_state.token_mint_id,
+            amount: 10 * account_state.reward_per_account,
             state: spl_token::state::AccountState::Initialized,
             owner: account_id.clone(),
             ..SplTokenAccount::default()
@@ -69,6 +89,22 @@ impl ProgramInfo {
             },
         );
 
+        // Pools sharing a mint add it again, the last one wins
+        let mut data_packed = vec![0; SplTokenMint::LEN];
+        SplTokenMint {
+            is_initialized: true,
+            ..SplTokenMint::default()
+        }.pack_into_slice(&mut data_packed);
+        program_test.add_account(
+            account_state.token_mint_id,
+            Account {
+                lamports: 1_000_000_000,
+                data: data_packed,
+                owner: account_state.token_program_id,
+                ..Account::default()
+            },
+        );
+
         ProgramInfo {
             pool_account_id: account_id,
             pool_token_account_id: token_account_id,
@@ -99,7 +135,7 @@ impl UserInfo {
         );
 
         let token_account_id = {
-            let id = config::get_claimer_token_account(&token_mint, &wallet_keypair.pubkey());
+            let id = config::get_claimer_token_account(&spl_token::id(), &token_mint, &wallet_keypair.pubkey());
             let data = SplTokenAccount {
                 mint: token_mint,
                 amount: 0,
@@ -164,3 +200,274 @@ impl UserInfo {
     }
 }
 
+pub fn add_token_account(program_test: &mut ProgramTest, token_account_id: Pubkey, token_mint_id: Pubkey, owner: Pubkey, amount: u64) {
+    let mut data_packed = vec![0; SplTokenAccount::LEN];
+    SplTokenAccount {
+        mint: token_mint_id,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..SplTokenAccount::default()
+    }.pack_into_slice(&mut data_packed);
+    program_test.add_account(
+        token_account_id,
+        Account {
+            lamports: 5,
+            data: data_packed,
+            owner: spl_token::id(),
+            ..Account::default()
+        },
+    );
+}
+
+
+// Builds the Claim instruction of a pool, the claimer wallet pays unless a payer is set
+#[derive(Clone)]
+pub struct ClaimBuilder {
+    program_id: Pubkey,
+    token_program_id: Pubkey,
+    token_mint_id: Pubkey,
+    pool_account_id: Pubkey,
+    payer: Option<Pubkey>,
+    gate_token_account: Option<Pubkey>,
+    fee_recipient: Option<Pubkey>,
+    reward_mint_id_list: Vec<Pubkey>,
+    referrer_wallet_list: Vec<Pubkey>,
+}
+
+impl ClaimBuilder {
+    pub fn new(program_id: Pubkey, token_mint_id: Pubkey, pool_account_id: Pubkey) -> ClaimBuilder {
+        ClaimBuilder {
+            program_id,
+            token_program_id: spl_token::id(),
+            token_mint_id,
+            pool_account_id,
+            payer: None,
+            gate_token_account: None,
+            fee_recipient: None,
+            reward_mint_id_list: vec![],
+            referrer_wallet_list: vec![],
+        }
+    }
+
+    pub fn token_program(mut self, token_program_id: Pubkey) -> ClaimBuilder {
+        self.token_program_id = token_program_id;
+        self
+    }
+
+    pub fn payer(mut self, payer: Pubkey) -> ClaimBuilder {
+        self.payer = Some(payer);
+        self
+    }
+
+    pub fn gate_token_account(mut self, gate_token_account: Pubkey) -> ClaimBuilder {
+        self.gate_token_account = Some(gate_token_account);
+        self
+    }
+
+    pub fn fee_recipient(mut self, fee_recipient: Pubkey) -> ClaimBuilder {
+        self.fee_recipient = Some(fee_recipient);
+        self
+    }
+
+    pub fn reward_mints(mut self, reward_mint_id_list: &[Pubkey]) -> ClaimBuilder {
+        self.reward_mint_id_list = reward_mint_id_list.to_vec();
+        self
+    }
+
+    pub fn referrers(mut self, referrer_wallet_list: &[Pubkey]) -> ClaimBuilder {
+        self.referrer_wallet_list = referrer_wallet_list.to_vec();
+        self
+    }
+
+    pub fn build(&self, claimer_wallet: Pubkey) -> Instruction {
+        AirdropPoolInstruction::claim(&self.get_account_keys(claimer_wallet), &self.referrer_wallet_list)
+    }
+
+    pub fn build_with_proof(&self, claimer_wallet: Pubkey, amount: u64, proof: Vec<[u8; 32]>) -> Instruction {
+        AirdropPoolInstruction::claim_with_proof(&self.get_account_keys(claimer_wallet), amount, proof)
+    }
+
+    fn get_account_keys(&self, claimer_wallet: Pubkey) -> ClaimAccountKeys {
+        ClaimAccountKeys {
+            payer: self.payer.unwrap_or(claimer_wallet),
+            program: self.program_id,
+            rent_sysvar: solana_program::sysvar::rent::id(),
+            clock_sysvar: solana_program::sysvar::clock::id(),
+            instructions_sysvar: solana_program::sysvar::instructions::id(),
+            system_program: solana_program::system_program::id(),
+            token_program: self.token_program_id,
+            associated_token_program: spl_associated_token_account::id(),
+            token_mint: self.token_mint_id,
+            pool_account: self.pool_account_id,
+            claimer_wallet,
+            gate_token_account: self.gate_token_account,
+            fee_recipient: self.fee_recipient,
+            reward_mint_list: self.reward_mint_id_list.clone(),
+        }
+    }
+}
+
+// Builds the Initialize instruction of a pool, the options default to a plain pool
+pub struct InitializeBuilder {
+    pub program_id: Pubkey,
+    pub token_program_id: Pubkey,
+    pub token_mint_id: Pubkey,
+    pub pool_account_nonce: [u8; 4],
+    pub reward_per_account: u64,
+    pub reward_per_referral: u64,
+    pub max_referral_depth: u8,
+    pub claim_start_unix_timestamp: Option<UnixTimestamp>,
+    pub claim_end_unix_timestamp: Option<UnixTimestamp>,
+    pub merkle_root: Option<[u8; 32]>,
+    pub voucher_signer: Option<Pubkey>,
+    pub vesting_duration_seconds: Option<u64>,
+    pub vesting_cliff_seconds: Option<u64>,
+    pub referral_rewards: Vec<u64>,
+    pub gate_mint: Option<Pubkey>,
+    pub gate_min_amount: u64,
+    pub claim_fee_lamports: u64,
+    pub fee_recipient: Pubkey,
+}
+
+impl InitializeBuilder {
+    pub fn new(program_id: Pubkey, token_mint_id: Pubkey, pool_account_nonce: [u8; 4]) -> InitializeBuilder {
+        InitializeBuilder {
+            program_id,
+            token_program_id: spl_token::id(),
+            token_mint_id,
+            pool_account_nonce,
+            reward_per_account: 0,
+            reward_per_referral: 0,
+            max_referral_depth: 0,
+            claim_start_unix_timestamp: None,
+            claim_end_unix_timestamp: None,
+            merkle_root: None,
+            voucher_signer: None,
+            vesting_duration_seconds: None,
+            vesting_cliff_seconds: None,
+            referral_rewards: vec![],
+            gate_mint: None,
+            gate_min_amount: 0,
+            claim_fee_lamports: 0,
+            fee_recipient: Pubkey::default(),
+        }
+    }
+
+    pub fn build(&self, payer: Pubkey) -> Instruction {
+        AirdropPoolInstruction::initialize(
+            payer,
+            self.program_id,
+            solana_program::sysvar::rent::id(),
+            solana_program::system_program::id(),
+            self.token_program_id,
+            self.token_mint_id,
+            InitializeArgs {
+                pool_account_nonce: self.pool_account_nonce,
+                reward_per_account: self.reward_per_account,
+                reward_per_referral: self.reward_per_referral,
+                max_referral_depth: self.max_referral_depth,
+                claim_start_unix_timestamp: self.claim_start_unix_timestamp,
+                claim_end_unix_timestamp: self.claim_end_unix_timestamp,
+                merkle_root: self.merkle_root,
+                voucher_signer: self.voucher_signer,
+                vesting_duration_seconds: self.vesting_duration_seconds,
+                vesting_cliff_seconds: self.vesting_cliff_seconds,
+                referral_rewards: self.referral_rewards.clone(),
+                gate_mint: self.gate_mint,
+                gate_min_amount: self.gate_min_amount,
+                claim_fee_lamports: self.claim_fee_lamports,
+                fee_recipient: self.fee_recipient,
+            },
+        )
+    }
+}
+
+pub async fn send_instructions(banks_client: &mut BanksClient,
+                               payer: &Keypair,
+                               recent_blockhash: Hash,
+                               instructions: &[Instruction],
+                               signers: &[&Keypair]) -> Result<(), BanksClientError> {
+    let mut transaction = Transaction::new_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+    );
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    transaction.sign(&all_signers, recent_blockhash);
+    banks_client.process_transaction(transaction).await
+}
+
+pub async fn send_instruction(banks_client: &mut BanksClient,
+                              payer: &Keypair,
+                              recent_blockhash: Hash,
+                              instruction: Instruction,
+                              signers: &[&Keypair]) -> Result<(), BanksClientError> {
+    send_instructions(banks_client, payer, recent_blockhash, &[instruction], signers).await
+}
+
+// The claimer wallet signs the claim, the payer pays the fees
+pub async fn send_claim(banks_client: &mut BanksClient,
+                        payer: &Keypair,
+                        recent_blockhash: Hash,
+                        claim: &ClaimBuilder,
+                        user_info: &UserInfo) -> Result<(), BanksClientError> {
+    send_instruction(banks_client, payer, recent_blockhash, claim.build(user_info.wallet.pubkey()), &[&user_info.wallet]).await
+}
+
+// The payer of the context pays the fees, with the latest blockhash after a warp
+pub async fn send_context_instruction(context: &mut ProgramTestContext,
+                                      instruction: Instruction,
+                                      signers: &[&Keypair]) -> Result<(), BanksClientError> {
+    let recent_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    send_instruction(&mut context.banks_client, &context.payer, recent_blockhash, instruction, signers).await
+}
+
+pub fn assert_error(result: Result<(), BanksClientError>, error: AirdropPoolError) {
+    assert_instruction_error(result, 0, error);
+}
+
+pub fn assert_instruction_error(result: Result<(), BanksClientError>, index: u8, error: AirdropPoolError) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(index, InstructionError::Custom(error as u32)),
+    );
+}
+
+// Warps to [slot] to get a fresh blockhash, then overrides the clock unix timestamp
+pub async fn set_unix_timestamp(context: &mut ProgramTestContext,
+                                slot: u64,
+                                unix_timestamp: Option<UnixTimestamp>) -> UnixTimestamp {
+    context.warp_to_slot(slot).unwrap();
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    if let Some(unix_timestamp) = unix_timestamp {
+        clock.unix_timestamp = unix_timestamp;
+        context.set_sysvar(&clock);
+    }
+    clock.unix_timestamp
+}
+
+// Reads SPL Token and Token-2022 accounts alike
+pub async fn get_token_amount(banks_client: &mut BanksClient, token_account_id: Pubkey) -> u64 {
+    let token_account = banks_client.get_account(token_account_id)
+        .await
+        .expect("token_account get_account failed")
+        .expect("token_account not found");
+    StateWithExtensions::<spl_token_2022::state::Account>::unpack(&token_account.data).unwrap().base.amount
+}
+
+pub async fn get_pool_state(banks_client: &mut BanksClient, pool_account_id: Pubkey) -> AirdropPool {
+    let pool_account = banks_client.get_account(pool_account_id)
+        .await
+        .expect("pool_account get_account failed")
+        .expect("pool_account not found");
+    AirdropPool::unpack(&pool_account.data).unwrap()
+}
+
+pub async fn get_claimer_state(banks_client: &mut BanksClient, claimer_account_id: Pubkey) -> AirdropClaimer {
+    let claimer_account = banks_client.get_account(claimer_account_id)
+        .await
+        .expect("claimer_account get_account failed")
+        .expect("claimer_account not found");
+    AirdropClaimer::unpack(&claimer_account.data).unwrap()
+}